@@ -134,6 +134,171 @@ fn write_squareset() {
     write("squareset_tables.rs", &code);
 }
 
+// Retrograde analysis of all king and pawn versus king positions, taken from Stockfish.
+// The strong side is always white with its pawn on files A to D.
+fn write_kpk() {
+    const SIZE: usize = 2 * 24 * 64 * 64;
+
+    const INVALID: u8 = 0b000;
+    const UNKNOWN: u8 = 0b001;
+    const DRAW: u8 = 0b010;
+    const WIN: u8 = 0b100;
+
+    const WHITE: usize = 0;
+    const BLACK: usize = 1;
+
+    fn encode(stm: usize, bksq: Square, wksq: Square, psq: Square) -> usize {
+        let (file, rank) = (psq.file() as usize, psq.rank() as usize);
+
+        wksq as usize | (bksq as usize) << 6 | stm << 12 | file << 13 | (6 - rank) << 15
+    }
+
+    fn king(sq: Square) -> SquareSet {
+        let mut set = SquareSet::EMPTY;
+
+        for (delta_file, delta_rank) in [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ] {
+            if let Some(sq) = sq.try_delta(delta_file, delta_rank) {
+                set = set | sq.set();
+            }
+        }
+
+        set
+    }
+
+    fn pawn(sq: Square) -> SquareSet {
+        let mut set = SquareSet::EMPTY;
+
+        for delta_file in [-1, 1] {
+            if let Some(sq) = sq.try_delta(delta_file, 1) {
+                set = set | sq.set();
+            }
+        }
+
+        set
+    }
+
+    fn decode(index: usize) -> (usize, Square, Square, Square) {
+        let wksq = Square::new((index & 0x3F) as u8).unwrap();
+        let bksq = Square::new(((index >> 6) & 0x3F) as u8).unwrap();
+        let stm = (index >> 12) & 1;
+
+        let file = File::new(((index >> 13) & 3) as u8).unwrap();
+        let rank = Rank::new(6 - ((index >> 15) & 7) as u8).unwrap();
+
+        (stm, bksq, wksq, Square::from(file, rank))
+    }
+
+    fn initial(index: usize) -> u8 {
+        let (stm, bksq, wksq, psq) = decode(index);
+        let push = psq.try_delta(0, 1).unwrap();
+
+        if wksq.distance(bksq) <= 1 || wksq == psq || bksq == psq {
+            return INVALID;
+        }
+
+        // Their king can't be in check if it is our turn
+        if stm == WHITE && pawn(psq).is_set(bksq) {
+            return INVALID;
+        }
+
+        // We can promote without getting captured
+        if stm == WHITE
+            && psq.rank() == Rank::Seven
+            && wksq != push
+            && bksq != push
+            && (bksq.distance(push) > 1 || king(wksq).is_set(push))
+        {
+            return WIN;
+        }
+
+        // Their king is stalemated, or can capture our undefended pawn
+        if stm == BLACK
+            && ((king(bksq) - (king(wksq) | pawn(psq))).is_empty()
+                || !(king(bksq) & psq.set() & !king(wksq)).is_empty())
+        {
+            return DRAW;
+        }
+
+        UNKNOWN
+    }
+
+    fn classify(db: &[u8], index: usize) -> u8 {
+        let (stm, bksq, wksq, psq) = decode(index);
+
+        let (good, bad) = if stm == WHITE {
+            (WIN, DRAW)
+        } else {
+            (DRAW, WIN)
+        };
+        let mut result = INVALID;
+
+        for sq in king(if stm == WHITE { wksq } else { bksq }).iter() {
+            result |= match stm {
+                WHITE => db[encode(BLACK, bksq, sq, psq)],
+                _ => db[encode(WHITE, sq, wksq, psq)],
+            };
+        }
+
+        if stm == WHITE {
+            let push = psq.try_delta(0, 1).unwrap();
+
+            if psq.rank() != Rank::Seven {
+                result |= db[encode(BLACK, bksq, wksq, push)];
+            }
+
+            if psq.rank() == Rank::Two && push != wksq && push != bksq {
+                let double = push.try_delta(0, 1).unwrap();
+                result |= db[encode(BLACK, bksq, wksq, double)];
+            }
+        }
+
+        if result & good != 0 {
+            good
+        } else if result & UNKNOWN != 0 {
+            UNKNOWN
+        } else {
+            bad
+        }
+    }
+
+    let mut db: Vec<u8> = (0..SIZE).map(initial).collect();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for index in 0..SIZE {
+            if db[index] != UNKNOWN {
+                continue;
+            }
+
+            db[index] = classify(&db, index);
+            changed |= db[index] != UNKNOWN;
+        }
+    }
+
+    let mut bitbase = vec![0u32; SIZE / 32];
+
+    for (index, result) in db.iter().enumerate() {
+        if *result == WIN {
+            bitbase[index / 32] |= 1 << (index % 32);
+        }
+    }
+
+    let code = format!("const KPK: &[u32; {}] = &{:?};", SIZE / 32, bitbase);
+
+    write("kpk.rs", &code);
+}
+
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=types/src/slider/magic.rs");
@@ -141,4 +306,5 @@ fn main() {
 
     write_slider();
     write_squareset();
+    write_kpk();
 }
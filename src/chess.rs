@@ -1,8 +1,10 @@
-mod attacks;
+pub mod attacks;
 mod board;
 mod movelist;
 mod position;
 
-pub use board::{All, Capture, FenParseError, GenerationType, Key, PieceLayout, Quiet};
+pub use board::{
+    All, Capture, FenParseError, GenerationType, Key, PieceLayout, Quiet, ValidationError,
+};
 pub use movelist::{MoveList, MoveListEntry};
 pub use position::Position;
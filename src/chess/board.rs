@@ -1,13 +1,18 @@
+mod cuckoo;
 mod draw;
 mod fen;
 mod layout;
 mod legal;
 mod movegen;
+mod see;
+mod validate;
 mod zobrist;
 
+pub use cuckoo::cuckoo;
 pub use fen::FenParseError;
 pub use layout::PieceLayout;
 pub use movegen::{All, Capture, GenerationType, Quiet};
+pub use validate::ValidationError;
 pub use zobrist::Key;
 
 use std::fmt::Display;
@@ -18,7 +23,7 @@ use types::{
     Rank, Square, SquareSet,
 };
 
-use crate::chess::position::GameState;
+use crate::{chess::position::GameState, evaluation};
 
 include!(concat!(env!("OUT_DIR"), "/squareset_tables.rs"));
 
@@ -61,6 +66,7 @@ impl Board {
 
         self.state.en_passant = None;
         self.state.rule50_ply += 1;
+        self.state.plies_from_null = self.state.plies_from_null.saturating_add(1);
         self.state.capture = None;
 
         // The fifty move counter is resetted on a pawn move
@@ -147,6 +153,26 @@ impl Board {
         );
     }
 
+    /// Pass the turn to the other side, which is only legal if we are not in check
+    #[cfg(test)]
+    pub fn make_null_move(&mut self, color: Color) {
+        debug_assert!(self.state.checkers.is_empty());
+
+        self.state.zobrist ^= zobrist::SIDE;
+
+        if let Some(target) = self.state.en_passant {
+            self.state.zobrist ^= zobrist::EN_PASSANT[target.file()];
+        }
+
+        self.state.en_passant = None;
+        self.state.rule50_ply += 1;
+        self.state.plies_from_null = 0;
+        self.state.capture = None;
+
+        self.state.set_blockers(!color, &self.layout);
+        self.state.set_checkers(!color, &self.layout);
+    }
+
     pub fn unmake_move(&mut self, mov: Move, color: Color, state: GameState) {
         let start = mov.start();
         let target = mov.target();
@@ -197,11 +223,54 @@ impl Board {
         self.state = state;
     }
 
-    fn toggle<const ZOBRIST: bool>(&mut self, sq: Square, color: Color, piece: PieceType) {
+    /// Mirror the board vertically and swap the colors of all pieces, where `color` is
+    /// the side to move after flipping
+    pub fn flip(&self, color: Color) -> Self {
+        let mut board = Self {
+            layout: PieceLayout::EMPTY,
+            state: GameState::EMPTY,
+        };
+
+        for sq in self.layout.all().iter() {
+            let piece = self.layout.at(sq).unwrap();
+            board.toggle::<true>(sq.flip(), !piece.color(), piece.typ());
+        }
+
+        board.state.rule50_ply = self.state.rule50_ply;
+        board.state.castling = self.state.castling.flip();
+        board.state.en_passant = self.state.en_passant.map(Square::flip);
+
+        if color == Color::Black {
+            board.state.zobrist ^= zobrist::SIDE;
+        }
+
+        board.state.zobrist ^= zobrist::CASTLING[board.state.castling];
+
+        if let Some(target) = board.state.en_passant {
+            board.state.zobrist ^= zobrist::EN_PASSANT[target.file()];
+        }
+
+        board.state.set_blockers(color, &board.layout);
+        board.state.set_checkers(color, &board.layout);
+
+        board
+    }
+
+    // Only the state of the position before the move is restored on unmake,
+    // so we don't have to update it again
+    fn toggle<const STATE: bool>(&mut self, sq: Square, color: Color, piece: PieceType) {
         self.layout.toggle(sq, color, piece);
 
-        if ZOBRIST {
+        if STATE {
             self.state.zobrist ^= zobrist::PIECE[color][piece][sq];
+
+            // Did we place or remove the piece?
+            let sign = if self.layout.at(sq).is_some() { 1 } else { -1 };
+            let (midgame, endgame) = evaluation::psq(color, piece, sq);
+
+            self.state.midgame[color] += sign * midgame;
+            self.state.endgame[color] += sign * endgame;
+            self.state.phase += sign * evaluation::PHASE[piece];
         }
     }
 }
@@ -0,0 +1,110 @@
+use types::{BISHOP, ROOK, Square, SquareSet, const_for};
+
+use crate::chess::board::zobrist::{Key, PIECE, SIDE};
+
+// Cuckoo hash tables of all reversible moves of non-pawn pieces on an empty board,
+// indexed by the change of the zobrist key, following Marcel van Kervinck's cycle detection
+const SIZE: usize = 8192;
+
+static TABLES: ([Key; SIZE], [Option<(Square, Square)>; SIZE]) = {
+    let mut keys = [0; SIZE];
+    let mut moves = [None; SIZE];
+    let mut count = 0;
+
+    // Knights, bishops, rooks, queens and kings of both colors
+    const_for!(let mut piece = 1; piece < 6; piece += 1; {
+        const_for!(let mut color = 0; color < 2; color += 1; {
+            const_for!(let mut a = 0; a < 64; a += 1; {
+                let attacks = attacks(piece, a);
+
+                const_for!(let mut b = a + 1; b < 64; b += 1; {
+                    if attacks & (1 << b) != 0 {
+                        let mut mov = Some((Square::new(a as u8).unwrap(), Square::new(b as u8).unwrap()));
+                        let mut key = PIECE[color][piece][a] ^ PIECE[color][piece][b] ^ *SIDE;
+                        let mut i = h1(key);
+
+                        // Displace the occupying entry into its other slot, until one is empty
+                        loop {
+                            (keys[i], key) = (key, keys[i]);
+                            (moves[i], mov) = (mov, moves[i]);
+
+                            if mov.is_none() {
+                                break;
+                            }
+
+                            i = if i == h1(key) { h2(key) } else { h1(key) };
+                        }
+
+                        count += 1;
+                    }
+                });
+            });
+        });
+    });
+
+    assert!(count == 3668);
+
+    (keys, moves)
+};
+
+/// Squares of the reversible move, which changes the zobrist key by `key`
+pub fn cuckoo(key: Key) -> Option<(Square, Square)> {
+    let (keys, moves) = &TABLES;
+
+    [h1(key), h2(key)]
+        .into_iter()
+        .find(|&i| keys[i] == key)
+        .and_then(|i| moves[i])
+}
+
+const fn h1(key: Key) -> usize {
+    (key & 0x1FFF) as usize
+}
+
+const fn h2(key: Key) -> usize {
+    ((key >> 16) & 0x1FFF) as usize
+}
+
+/// Attacks of the piece with the given index from `sq` on an empty board
+const fn attacks(piece: usize, sq: usize) -> u64 {
+    const KNIGHT: [(i8, i8); 8] = [
+        (1, 2),
+        (2, 1),
+        (2, -1),
+        (1, -2),
+        (-1, -2),
+        (-2, -1),
+        (-2, 1),
+        (-1, 2),
+    ];
+    const KING: [(i8, i8); 8] = [
+        (1, 1),
+        (1, 0),
+        (1, -1),
+        (0, -1),
+        (-1, -1),
+        (-1, 0),
+        (-1, 1),
+        (0, 1),
+    ];
+
+    let sq = Square::new(sq as u8).unwrap();
+
+    let deltas = match piece {
+        2 => return BISHOP.moves(sq, SquareSet::EMPTY).0,
+        3 => return ROOK.moves(sq, SquareSet::EMPTY).0,
+        4 => return BISHOP.moves(sq, SquareSet::EMPTY).0 | ROOK.moves(sq, SquareSet::EMPTY).0,
+        1 => KNIGHT,
+        _ => KING,
+    };
+
+    let mut attacks = 0;
+
+    const_for!(let mut i = 0; i < deltas.len(); i += 1; {
+        if let Some(target) = sq.try_delta(deltas[i].0, deltas[i].1) {
+            attacks |= target.set().0;
+        }
+    });
+
+    attacks
+}
@@ -1,16 +1,79 @@
+use std::fmt::Display;
+
 use types::{Color, File, Piece, Rank, Square};
 
-use crate::{
-    chess::{
-        board::{Board, zobrist},
-        position::GameState,
-    },
-    ok_or, syntax_error,
+use crate::chess::{
+    board::{Board, ValidationError, zobrist},
+    position::GameState,
 };
 
 use super::layout::PieceLayout;
 
-pub type FenParseError = String;
+/// The error type, which is returned from parsing a FEN
+#[derive(Debug, PartialEq)]
+pub enum FenParseError {
+    /// The FEN doesn't consist of exactly six fields.
+    FieldCount(usize),
+    /// The field with the given index contains an unexpected token.
+    Field {
+        index: usize,
+        expected: &'static str,
+        found: String,
+    },
+    /// The symbol is not a valid piece.
+    Piece(char),
+    /// The en passant field is not a valid square.
+    Square(String),
+    /// The position itself is inconsistent.
+    Invalid(ValidationError),
+}
+
+impl Display for FenParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FieldCount(count) => write!(f, "expected 6 fields, but found {}", count),
+            Self::Field {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {} in field {}, but found {}",
+                expected,
+                index + 1,
+                found
+            ),
+            Self::Piece(symbol) => write!(f, "invalid piece symbol {}", symbol),
+            Self::Square(square) => write!(f, "invalid square {}", square),
+            Self::Invalid(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for FenParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Invalid(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for FenParseError {
+    fn from(value: ValidationError) -> Self {
+        Self::Invalid(value)
+    }
+}
+
+impl FenParseError {
+    fn field(index: usize, expected: &'static str, found: impl Display) -> Self {
+        Self::Field {
+            index,
+            expected,
+            found: found.to_string(),
+        }
+    }
+}
 
 impl Board {
     pub fn from_fen(fen: &str) -> Result<(Self, Color, usize), FenParseError> {
@@ -21,11 +84,16 @@ impl Board {
 
         let fields: Vec<&str> = fen.split_ascii_whitespace().collect();
         if fields.len() != 6 {
-            return Err(format!("expected 6 fields, but found {}", fields.len()));
+            return Err(FenParseError::FieldCount(fields.len()));
         }
 
-        let stm = ok_or!(Color::try_from(fields[1]).ok(), "'w' or 'b'", fields[1]);
-        let fullmove = ok_or!(fields[5].parse().ok(), "integer", fields[5]);
+        let stm = Color::try_from(fields[1])
+            .map_err(|_| FenParseError::field(1, "'w' or 'b'", fields[1]))?;
+        let fullmove = fields[5]
+            .parse()
+            .ok()
+            .filter(|&fullmove| fullmove > 0)
+            .ok_or_else(|| FenParseError::field(5, "positive integer", fields[5]))?;
 
         if stm == Color::Black {
             board.state.zobrist ^= zobrist::SIDE;
@@ -35,7 +103,12 @@ impl Board {
         board.parse_castling(fields[2])?;
         board.parse_en_passant(fields[3])?;
 
-        board.state.rule50_ply = ok_or!(fields[4].parse().ok(), "integer", fields[4]);
+        board.state.rule50_ply = fields[4]
+            .parse()
+            .map_err(|_| FenParseError::field(4, "integer", fields[4]))?;
+
+        // We reject inconsistent positions, before any move generation relies on them
+        board.validate(stm)?;
 
         board.state.set_blockers(stm, &board.layout);
         board.state.set_checkers(stm, &board.layout);
@@ -60,29 +133,36 @@ impl Board {
     }
 
     fn parse_board(&mut self, fen: &str) -> Result<(), FenParseError> {
-        let (mut col, mut row) = (0, 7);
+        let ranks: Vec<&str> = fen.split('/').collect();
 
-        for c in fen.chars() {
-            if let Some(delta) = c.to_digit(10) {
-                col += delta as u8;
+        if ranks.len() != 8 {
+            return Err(FenParseError::field(0, "8 ranks", fen));
+        }
 
-                continue;
-            }
+        for (row, rank) in ranks.into_iter().rev().enumerate() {
+            let error = || FenParseError::field(0, "8 squares per rank", rank);
+            let mut col: u8 = 0;
 
-            if c == '/' {
-                (col, row) = (0, row - 1);
+            for c in rank.chars() {
+                if let Some(delta) = c.to_digit(10) {
+                    col = col.checked_add(delta as u8).ok_or_else(error)?;
 
-                continue;
-            }
+                    continue;
+                }
 
-            let file = ok_or!(File::new(col), "valid file index", col);
-            let rank = ok_or!(Rank::new(row), "valid rank index", row);
+                let file = File::new(col).ok_or_else(error)?;
 
-            let piece = Piece::try_from(c).map_err(|err| format!("{:?}", err))?;
-            let sq = Square::from(file, rank);
+                let piece = Piece::try_from(c).map_err(|_| FenParseError::Piece(c))?;
+                let sq = Square::from(file, Rank::new(row as u8).unwrap());
+
+                self.toggle::<true>(sq, piece.color(), piece.typ());
+                col += 1;
+            }
 
-            self.toggle::<true>(sq, piece.color(), piece.typ());
-            col += 1;
+            // Each rank has to cover all of its squares exactly
+            if col != 8 {
+                return Err(error());
+            }
         }
 
         Ok(())
@@ -96,7 +176,7 @@ impl Board {
                 'k' => self.state.castling.set_kingside(Color::Black),
                 'q' => self.state.castling.set_queenside(Color::Black),
                 '-' if fen.len() == 1 => continue,
-                _ => return Err(format!("Expected 'KQkq' subset or '-', but found {}", c)),
+                _ => return Err(FenParseError::field(2, "'KQkq' subset or '-'", c)),
             };
         }
 
@@ -110,10 +190,12 @@ impl Board {
             return Ok(());
         }
 
-        let mov = fen.as_bytes();
+        let square = || FenParseError::Square(fen.to_string());
+
+        let [file, rank] = <[u8; 2]>::try_from(fen.as_bytes()).map_err(|_| square())?;
 
-        let file = ok_or!(File::new(mov[0] - b'a'), "valid move", fen);
-        let rank = ok_or!(Rank::new(mov[1] - b'1'), "valid", fen);
+        let file = File::new(file.wrapping_sub(b'a')).ok_or_else(square)?;
+        let rank = Rank::new(rank.wrapping_sub(b'1')).ok_or_else(square)?;
 
         self.state.en_passant = Some(Square::from(file, rank));
         self.state.zobrist ^= zobrist::EN_PASSANT[file];
@@ -124,7 +206,10 @@ impl Board {
 
 #[cfg(test)]
 mod tests {
-    use crate::{FEN, chess::Position};
+    use crate::{
+        FEN,
+        chess::{Position, board::Board, board::FenParseError},
+    };
 
     #[test]
     fn parse() {
@@ -134,4 +219,48 @@ mod tests {
             assert_eq!(fen, pos.fen().unwrap());
         }
     }
+
+    #[test]
+    fn errors() {
+        let field = |index, expected, found: &str| FenParseError::Field {
+            index,
+            expected,
+            found: found.to_string(),
+        };
+
+        let expected = [
+            ("4k3/8/8/8/8/8/8/4K3 w - -", FenParseError::FieldCount(4)),
+            ("4k3/8/8/8/8/8/8/4K3 x - - 0 1", field(1, "'w' or 'b'", "x")),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w X - 0 1",
+                field(2, "'KQkq' subset or '-'", "X"),
+            ),
+            ("4k3/8/8/8/8/8/8/4K3 w - - a 1", field(4, "integer", "a")),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w - - 0 0",
+                field(5, "positive integer", "0"),
+            ),
+            ("4k3/8/8/8/8/8/8/4X3 w - - 0 1", FenParseError::Piece('X')),
+            (
+                "N99999999999999999999999999993b7/8/8/8/8/k7/8/4K3 w - - 0 1",
+                field(0, "8 squares per rank", "N99999999999999999999999999993b7"),
+            ),
+            (
+                "4k4/8/8/8/8/8/8/4K3 w - - 0 1",
+                field(0, "8 squares per rank", "4k4"),
+            ),
+            (
+                "4k3/8/8/8/8/8/4K3 w - - 0 1",
+                field(0, "8 ranks", "4k3/8/8/8/8/8/4K3"),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w - e9 0 1",
+                FenParseError::Square("e9".to_string()),
+            ),
+        ];
+
+        for (fen, error) in expected {
+            assert_eq!(Some(error), Board::from_fen(fen).err(), "{}", fen);
+        }
+    }
 }
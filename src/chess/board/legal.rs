@@ -98,11 +98,9 @@ impl Board {
             let capture = target.set().rotate([56, 8][color]);
             let occ = (self.layout.all() - start.set() - capture) | target.set();
 
-            let rooks = attacks::rook(king, occ) & self.layout.orthogonal();
-            let bishops = attacks::bishop(king, occ) & self.layout.diagonal();
-
-            // Is our king in check after making the en passant capture?
-            return ((rooks | bishops) & self.layout.color(!color)).is_empty();
+            // Is our king in check after making the en passant capture? Besides a discovered
+            // slider, the capture doesn't resolve a check by any other piece than the pawn
+            return (self.layout.attackers(king, color, occ) - capture).is_empty();
         }
 
         // If the king moves, we must check if the target square is being attacked or not
@@ -116,12 +114,62 @@ impl Board {
         (self.state.blockers & start.set()).is_empty()
             || !(LINE[start][target] & self.layout.king(color).set()).is_empty()
     }
+
+    /// Does `mov` check their king, either directly, by discovery, or by the rook when castling?
+    pub fn gives_check(&self, mov: Move, color: Color) -> bool {
+        let start = mov.start();
+        let target = mov.target();
+        let flag = mov.flag();
+
+        let king = self.layout.king(!color);
+        let occ = self.layout.all();
+
+        // A promoted piece moves away from `start`, which might open a line to their king
+        let direct = match flag.piece() {
+            Some(piece) => attacks::by_type(piece, target, occ - start.set()).is_set(king),
+            None => self.state.check_squares[self.layout.unchecked_at(start)].is_set(target),
+        };
+
+        if direct {
+            return true;
+        }
+
+        // We discover a check, unless we move along the line to their king
+        if self.state.discoverers.is_set(start) && !LINE[start][target].is_set(king) {
+            return true;
+        }
+
+        match flag {
+            MoveFlag::EN_PASSANT => {
+                let capture = target.set().rotate([56, 8][color]);
+                let occ = (occ - start.set() - capture) | target.set();
+
+                let rooks = attacks::rook(king, occ) & self.layout.orthogonal();
+                let bishops = attacks::bishop(king, occ) & self.layout.diagonal();
+
+                // Both captured and capturing pawn might have blocked one of our sliders
+                !((rooks | bishops) & self.layout.color(color)).is_empty()
+            }
+            MoveFlag::KING_CASTLE | MoveFlag::QUEEN_CASTLE => {
+                let rook = match flag {
+                    MoveFlag::KING_CASTLE => Self::KING_CASTLE_START[color],
+                    _ => Self::QUEEN_CASTLE_START[color],
+                };
+
+                let occ = (occ - start.set()) | target.set();
+                attacks::rook(rook, occ).is_set(king)
+            }
+            _ => false,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use types::{Move, MoveFlag};
+
     use crate::{
-        FEN,
+        CORPUS, FEN,
         chess::{All, MoveList, Position},
     };
 
@@ -136,4 +184,83 @@ mod tests {
             assert!(moves.iter().all(|mov| pos.pseudo_legal(mov)));
         }
     }
+
+    #[test]
+    fn generate_legal() {
+        let legal = |pos: &Position| {
+            let (mut moves, mut pseudo) = (MoveList::new(), MoveList::new());
+
+            pos.generate_legal::<All>(&mut moves);
+            pos.generate::<All>(&mut pseudo);
+
+            let pseudo: Vec<_> = pseudo.iter().filter(|&mov| pos.legal(mov)).collect();
+
+            assert_eq!(moves.len(), pseudo.len(), "{}", pos.fen().unwrap());
+            assert!(
+                moves
+                    .iter()
+                    .all(|mov| pseudo.iter().any(|other| other.inner() == mov.inner()))
+            );
+
+            moves
+        };
+
+        // The en passant capture doesn't resolve the check of the knight
+        let pos = Position::from_fen("4k3/8/8/3pP3/8/5n2/8/4K3 w - d6 0 1").unwrap();
+        assert!(
+            legal(&pos)
+                .iter()
+                .all(|mov| mov.flag() != MoveFlag::EN_PASSANT)
+        );
+
+        for fen in FEN.iter().chain(CORPUS.iter()) {
+            let mut pos = Position::from_fen(fen).unwrap();
+
+            for mov in legal(&pos).iter() {
+                pos.make_move(mov);
+                legal(&pos);
+                pos.unmake_move(mov);
+            }
+        }
+    }
+
+    #[test]
+    fn gives_check() {
+        for fen in FEN.iter().chain(CORPUS.iter()) {
+            let mut pos = Position::from_fen(fen).unwrap();
+
+            let mut moves = MoveList::new();
+            pos.generate_legal::<All>(&mut moves);
+
+            // We also verify the replies, to cover checks by black
+            for mov in moves.iter() {
+                assert_eq!(
+                    pos.gives_check(mov),
+                    check(&mut pos, mov),
+                    "{} {}",
+                    fen,
+                    mov
+                );
+
+                pos.make_move(mov);
+
+                let mut replies = MoveList::new();
+                pos.generate_legal::<All>(&mut replies);
+
+                for reply in replies.iter() {
+                    assert_eq!(pos.gives_check(reply), check(&mut pos, reply));
+                }
+
+                pos.unmake_move(mov);
+            }
+        }
+    }
+
+    fn check(pos: &mut Position, mov: Move) -> bool {
+        pos.make_move(mov);
+        let check = pos.check();
+        pos.unmake_move(mov);
+
+        check
+    }
 }
@@ -3,7 +3,7 @@ use types::{Color, Move, MoveFlag, PieceType, Rank, Square, SquareSet};
 use crate::{
     chess::{
         MoveList, attacks,
-        board::{BETWEEN, Board},
+        board::{BETWEEN, Board, LINE},
     },
     push_loop,
 };
@@ -46,13 +46,24 @@ impl Board {
 
         // Is our king not in check?
         match checkers.is_empty() {
-            true => self.generate_all::<false, TYPE>(moves, color, checkers),
-            false => self.generate_all::<true, TYPE>(moves, color, checkers),
+            true => self.generate_all::<false, false, TYPE>(moves, color, checkers),
+            false => self.generate_all::<true, false, TYPE>(moves, color, checkers),
+        }
+    }
+
+    /// Generate all legal moves, where blockers of our king only move along the line to it,
+    /// and only king moves, en passant and castling have to be checked individually.
+    pub fn generate_legal<TYPE: GenerationType>(&self, moves: &mut MoveList, color: Color) {
+        let checkers = self.state.checkers;
+
+        match checkers.is_empty() {
+            true => self.generate_all::<false, true, TYPE>(moves, color, checkers),
+            false => self.generate_all::<true, true, TYPE>(moves, color, checkers),
         }
     }
 
     #[inline(always)]
-    fn generate_all<const EVADING: bool, TYPE: GenerationType>(
+    fn generate_all<const EVADING: bool, const LEGAL: bool, TYPE: GenerationType>(
         &self,
         moves: &mut MoveList,
         color: Color,
@@ -71,12 +82,12 @@ impl Board {
                 false => !self.layout.color(color),
             };
 
-            self.generate_pawns::<TYPE>(moves, color, target, occ);
+            self.generate_pawns::<LEGAL, TYPE>(moves, color, target, occ);
 
-            self.generate_attacks::<TYPE, { PieceType::Knight }>(moves, color, target, occ);
-            self.generate_attacks::<TYPE, { PieceType::Bishop }>(moves, color, target, occ);
-            self.generate_attacks::<TYPE, { PieceType::Rook }>(moves, color, target, occ);
-            self.generate_attacks::<TYPE, { PieceType::Queen }>(moves, color, target, occ);
+            self.generate_attacks::<LEGAL, TYPE, { PieceType::Knight }>(moves, color, target, occ);
+            self.generate_attacks::<LEGAL, TYPE, { PieceType::Bishop }>(moves, color, target, occ);
+            self.generate_attacks::<LEGAL, TYPE, { PieceType::Rook }>(moves, color, target, occ);
+            self.generate_attacks::<LEGAL, TYPE, { PieceType::Queen }>(moves, color, target, occ);
         }
 
         // Is `target` potentially representing the line between our king and the threatening piece?
@@ -85,16 +96,16 @@ impl Board {
             target = !self.layout.color(color);
         }
 
-        self.generate_attacks::<TYPE, { PieceType::King }>(moves, color, target, occ);
+        self.generate_attacks::<LEGAL, TYPE, { PieceType::King }>(moves, color, target, occ);
 
         // We can't castle, if either our king is in check or we already did it
         if TYPE::QUIET && !EVADING && !self.state.castling.is_empty(color) {
-            self.generate_castling(moves, color, occ);
+            self.generate_castling::<LEGAL>(moves, color, occ);
         }
     }
 
     #[inline(always)]
-    fn generate_pawns<TYPE: GenerationType>(
+    fn generate_pawns<const LEGAL: bool, TYPE: GenerationType>(
         &self,
         moves: &mut MoveList,
         color: Color,
@@ -105,6 +116,7 @@ impl Board {
 
         for start in (self.layout.get(PieceType::Pawn) & self.layout.color(color)).iter() {
             let set = start.set();
+            let target = self.restrict::<LEGAL>(start, color, target);
 
             // The intersection between our attacks and their pieces yields all captures
             let captures = attacks::pawn(color, start) & self.layout.color(!color);
@@ -129,10 +141,13 @@ impl Board {
             // The moves for the first case are generated via single rank shift
             let promo = (set & Self::DOUBLE_PUSH[!color]).rotate(Self::PAWN_ROTATION[color]) - occ;
 
-            // We consider both quiet and capture promotions for queens in quiescence search
-            let promo_flag = MoveFlag::new_promotion(PieceType::Queen);
-            push_loop!(moves, promo & target, start, promo_flag);
-            push_loop!(moves, promo_captures & target, start, promo_flag);
+            // We consider both quiet and capture promotions for queens in quiescence search,
+            // so they are part of the captures only
+            if TYPE::CAPTURE {
+                let promo_flag = MoveFlag::new_promotion(PieceType::Queen);
+                push_loop!(moves, promo & target, start, promo_flag);
+                push_loop!(moves, promo_captures & target, start, promo_flag);
+            }
 
             // We have to generate each possible piece and target square combination
             for piece in [PieceType::Rook, PieceType::Bishop, PieceType::Knight] {
@@ -149,11 +164,16 @@ impl Board {
 
             // There exists an en passant capture if we have a valid target square,
             // which our pawn can attack
+            // Both pawns leave the line to our king, so we have to check the capture as a whole
             if TYPE::CAPTURE
                 && let Some(target) = self.state.en_passant
                 && !(target.set() & attacks::pawn(color, start)).is_empty()
             {
-                moves.push(Move::new(start, target, MoveFlag::EN_PASSANT));
+                let mov = Move::new(start, target, MoveFlag::EN_PASSANT);
+
+                if !LEGAL || self.legal(mov, color) {
+                    moves.push(mov);
+                }
             }
 
             if !TYPE::QUIET {
@@ -180,7 +200,7 @@ impl Board {
     }
 
     #[inline(always)]
-    fn generate_attacks<TYPE: GenerationType, const PIECE: PieceType>(
+    fn generate_attacks<const LEGAL: bool, TYPE: GenerationType, const PIECE: PieceType>(
         &self,
         moves: &mut MoveList,
         color: Color,
@@ -190,7 +210,21 @@ impl Board {
         let pieces = self.layout.get(PIECE) & self.layout.color(color);
 
         for start in pieces.iter() {
-            let attacks = attacks::const_by_type::<PIECE>(start, occ);
+            let mut attacks = attacks::const_by_type::<PIECE>(start, occ);
+
+            // Our king can't move onto an attacked square, where it doesn't block
+            // the slider behind it anymore
+            if LEGAL && PIECE == PieceType::King {
+                let occ = occ - start.set();
+
+                for sq in (attacks & target).iter() {
+                    if !self.layout.attackers(sq, color, occ).is_empty() {
+                        attacks.toggle(sq);
+                    }
+                }
+            }
+
+            let target = self.restrict::<LEGAL>(start, color, target);
 
             // The intersection between our attacks and their pieces yields all captures
             if TYPE::CAPTURE {
@@ -207,18 +241,47 @@ impl Board {
     }
 
     #[inline(always)]
-    fn generate_castling(&self, moves: &mut MoveList, color: Color, occ: SquareSet) {
+    fn generate_castling<const LEGAL: bool>(
+        &self,
+        moves: &mut MoveList,
+        color: Color,
+        occ: SquareSet,
+    ) {
         const KING_TARGET: [Square; 2] = [Square::G1, Square::G8];
         const QUEEN_TARGET: [Square; 2] = [Square::C1, Square::C8];
 
         let king = self.layout.king(color);
 
+        // The king must not pass an attacked square
         if self.state.castling.pseudo_kingside(color, occ) {
-            moves.push(Move::new(king, KING_TARGET[color], MoveFlag::KING_CASTLE));
+            let mov = Move::new(king, KING_TARGET[color], MoveFlag::KING_CASTLE);
+
+            if !LEGAL || self.legal(mov, color) {
+                moves.push(mov);
+            }
         }
 
         if self.state.castling.pseudo_queenside(color, occ) {
-            moves.push(Move::new(king, QUEEN_TARGET[color], MoveFlag::QUEEN_CASTLE));
+            let mov = Move::new(king, QUEEN_TARGET[color], MoveFlag::QUEEN_CASTLE);
+
+            if !LEGAL || self.legal(mov, color) {
+                moves.push(mov);
+            }
         }
     }
+
+    /// A blocker of our king can only move along the line between our king and the slider
+    #[inline(always)]
+    fn restrict<const LEGAL: bool>(
+        &self,
+        start: Square,
+        color: Color,
+        target: SquareSet,
+    ) -> SquareSet {
+        if !LEGAL || !self.state.blockers.is_set(start) {
+            return target;
+        }
+
+        target & LINE[self.layout.king(color)][start]
+    }
 }
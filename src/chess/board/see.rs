@@ -0,0 +1,146 @@
+use types::{
+    Color, Move, MoveFlag,
+    PieceType::{Bishop, King, Knight, Pawn, Queen, Rook},
+    Square,
+};
+
+use crate::chess::{attacks, board::Board};
+
+// Rough piece values for static exchange evaluation, where the king can't be captured
+const VALUE: [i32; 6] = [100, 300, 300, 500, 900, 0];
+
+impl Board {
+    /// Static exchange evaluation of `mov` of `color`, which is true, if we win at least
+    /// `threshold` after all captures on the target square were made
+    pub fn see(&self, mov: Move, color: Color, threshold: i32) -> bool {
+        let (start, target, flag) = (mov.start(), mov.target(), mov.flag());
+
+        // Castling can't lose any material
+        if matches!(flag, MoveFlag::KING_CASTLE | MoveFlag::QUEEN_CASTLE) {
+            return threshold <= 0;
+        }
+
+        let mut occ = self.layout.all() - start.set() - target.set();
+
+        let capture = match flag {
+            MoveFlag::EN_PASSANT => {
+                let sq = Square::from(target.file(), Self::EN_PASSANT_CAPTURE[!color]);
+                occ = occ - sq.set();
+
+                Some(Pawn)
+            }
+            _ => self.layout.at(target).map(|piece| piece.typ()),
+        };
+
+        // Even if they don't recapture, we don't gain enough
+        let mut swap = capture.map_or(0, |piece| VALUE[piece]) - threshold;
+
+        if swap < 0 {
+            return false;
+        }
+
+        // Even if they recapture, we still gain enough
+        swap = VALUE[self.layout.unchecked_at(start)] - swap;
+
+        if swap <= 0 {
+            return true;
+        }
+
+        let mut attackers = self.layout.attackers(target, Color::White, occ)
+            | self.layout.attackers(target, Color::Black, occ);
+
+        let mut stm = color;
+        let mut win = true;
+
+        loop {
+            stm = !stm;
+            attackers = attackers & occ;
+
+            let ours = attackers & self.layout.color(stm);
+
+            if ours.is_empty() {
+                break;
+            }
+
+            win = !win;
+
+            // We always recapture with our least valuable piece
+            let piece = [Pawn, Knight, Bishop, Rook, Queen, King]
+                .into_iter()
+                .find(|&piece| !(ours & self.layout.get(piece)).is_empty())
+                .unwrap();
+
+            // Our king can only recapture, if they have no attacker left
+            if piece == King {
+                return if (attackers - ours).is_empty() {
+                    win
+                } else {
+                    !win
+                };
+            }
+
+            swap = VALUE[piece] - swap;
+
+            if swap < i32::from(win) {
+                break;
+            }
+
+            let set = ours & self.layout.get(piece);
+            occ = occ - set.iter().next().unwrap().set();
+
+            // Sliders behind the recapturing piece can join the exchange
+            if matches!(piece, Pawn | Bishop | Queen) {
+                attackers = attackers | (attacks::bishop(target, occ) & self.layout.diagonal());
+            }
+
+            if matches!(piece, Rook | Queen) {
+                attackers = attackers | (attacks::rook(target, occ) & self.layout.orthogonal());
+            }
+        }
+
+        win
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chess::{All, MoveList, board::Board};
+
+    #[test]
+    fn see() {
+        for (fen, mov, threshold, expected) in [
+            // A free pawn
+            ("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", "e4d5", 100, true),
+            ("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", "e4d5", 101, false),
+            ("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6", 100, true),
+            // A defended pawn for our queen
+            ("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", "d1d5", 0, false),
+            ("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", "d1d5", -800, true),
+            // The queen behind our rook joins the exchange, unless they have more attackers
+            ("3rk3/8/8/3p4/8/8/3R4/3QK3 w - - 0 1", "d2d5", 100, true),
+            ("3rk3/8/8/3p4/8/8/3R4/3QK3 w - - 0 1", "d2d5", 101, false),
+            ("3rk3/3r4/8/3p4/8/8/3R4/3QK3 w - - 0 1", "d2d5", 0, false),
+            // Their king can't recapture a defended piece
+            ("8/8/4k3/3p4/8/8/8/3RK2B w - - 0 1", "d1d5", 100, true),
+            ("8/8/4k3/3p4/8/8/8/3RK3 w - - 0 1", "d1d5", 0, false),
+            // A quiet move onto an attacked square
+            ("4k3/8/8/3p4/8/6N1/8/4K3 w - - 0 1", "g3e4", 0, false),
+            ("4k3/8/8/3p4/8/6N1/8/4K3 w - - 0 1", "g3e4", -300, true),
+            ("4k3/8/8/3p4/8/6N1/8/4K3 w - - 0 1", "g3e2", 0, true),
+        ] {
+            let (board, stm, _) = Board::from_fen(fen).unwrap();
+
+            let mut moves = MoveList::new();
+            board.generate_legal::<All>(&mut moves, stm);
+
+            let found = moves.iter().find(|m| format!("{}", m) == mov).unwrap();
+            assert_eq!(
+                expected,
+                board.see(found, stm, threshold),
+                "{} {}",
+                fen,
+                mov
+            );
+        }
+    }
+}
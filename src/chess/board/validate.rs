@@ -0,0 +1,168 @@
+use std::fmt::Display;
+
+use types::{
+    Color,
+    PieceType::{King, Pawn, Rook},
+    Rank, Square,
+};
+
+use crate::chess::board::Board;
+
+/// Reasons, why a parsed position can't be reached in a legal game
+#[derive(Debug, PartialEq)]
+pub enum ValidationError {
+    /// The side of the given color has not exactly one king.
+    KingCount(Color, u32),
+    /// There is a pawn on the first or last rank.
+    PawnOnBackRank(Square),
+    /// The side, which is not to move, is in check.
+    OpponentInCheck,
+    /// The side of the given color may castle, but its king has already moved.
+    CastlingWithoutKing(Color),
+    /// There is no rook of the castling side on the given square.
+    CastlingWithoutRook(Square),
+    /// No pawn can have just double pushed past the given square.
+    InvalidEnPassant(Square),
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KingCount(color, count) => {
+                write!(f, "expected one king for {:?}, but found {}", color, count)
+            }
+            Self::PawnOnBackRank(sq) => write!(f, "pawn on back rank at {}", sq),
+            Self::OpponentInCheck => write!(f, "side not to move is in check"),
+            Self::CastlingWithoutKing(color) => {
+                write!(
+                    f,
+                    "castling rights for {:?} without king on its start",
+                    color
+                )
+            }
+            Self::CastlingWithoutRook(sq) => write!(f, "castling rights without rook on {}", sq),
+            Self::InvalidEnPassant(sq) => write!(f, "impossible en passant square {}", sq),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl Board {
+    const KING_START: [Square; 2] = [Square::E1, Square::E8];
+
+    /// Check that the position is consistent, so we can safely generate moves for `stm`
+    pub fn validate(&self, stm: Color) -> Result<(), ValidationError> {
+        let layout = &self.layout;
+
+        for color in [Color::White, Color::Black] {
+            let count = (layout.get(King) & layout.color(color)).popcnt();
+
+            if count != 1 {
+                return Err(ValidationError::KingCount(color, count));
+            }
+        }
+
+        let back_ranks = Rank::One.set() | Rank::Eight.set();
+
+        if let Some(sq) = (layout.get(Pawn) & back_ranks).iter().next() {
+            return Err(ValidationError::PawnOnBackRank(sq));
+        }
+
+        if !layout
+            .attackers(layout.king(!stm), !stm, layout.all())
+            .is_empty()
+        {
+            return Err(ValidationError::OpponentInCheck);
+        }
+
+        let castling = self.state.castling;
+
+        for color in [Color::White, Color::Black] {
+            if castling.is_empty(color) {
+                continue;
+            }
+
+            if layout.king(color) != Self::KING_START[color] {
+                return Err(ValidationError::CastlingWithoutKing(color));
+            }
+
+            let rooks = layout.get(Rook) & layout.color(color);
+
+            for (allowed, sq) in [
+                (castling.kingside(color), Self::KING_CASTLE_TARGET[color]),
+                (castling.queenside(color), Self::QUEEN_CASTLE_TARGET[color]),
+            ] {
+                if allowed && !rooks.is_set(sq) {
+                    return Err(ValidationError::CastlingWithoutRook(sq));
+                }
+            }
+        }
+
+        if let Some(target) = self.state.en_passant {
+            // The pushed pawn is in front of the target square, and passed an empty square
+            let rank = [Rank::Six, Rank::Three][stm];
+            let pawn = target.set().rotate(Self::PAWN_ROTATION[!stm]);
+            let start = target.set().rotate(Self::PAWN_ROTATION[stm]);
+
+            if target.rank() != rank
+                || (pawn & layout.get(Pawn) & layout.color(!stm)).is_empty()
+                || !((target.set() | start) & layout.all()).is_empty()
+            {
+                return Err(ValidationError::InvalidEnPassant(target));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{Color, Square};
+
+    use crate::chess::board::{Board, FenParseError, validate::ValidationError};
+
+    #[test]
+    fn validate() {
+        const EXPECTED: [(&str, ValidationError); 7] = [
+            (
+                "8/8/8/8/8/8/8/4K3 w - - 0 1",
+                ValidationError::KingCount(Color::Black, 0),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/3KK3 w - - 0 1",
+                ValidationError::KingCount(Color::White, 2),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/P3K3 w - - 0 1",
+                ValidationError::PawnOnBackRank(Square::A1),
+            ),
+            (
+                "4k2R/8/8/8/8/8/8/4K3 w - - 0 1",
+                ValidationError::OpponentInCheck,
+            ),
+            (
+                "4k3/8/8/8/8/8/8/5K1R w K - 0 1",
+                ValidationError::CastlingWithoutKing(Color::White),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w q - 0 1",
+                ValidationError::CastlingWithoutRook(Square::A8),
+            ),
+            (
+                "4k3/8/8/8/4P3/8/8/4K3 w - e3 0 1",
+                ValidationError::InvalidEnPassant(Square::E3),
+            ),
+        ];
+
+        for (fen, error) in EXPECTED {
+            assert_eq!(
+                Some(FenParseError::Invalid(error)),
+                Board::from_fen(fen).err(),
+                "{}",
+                fen
+            );
+        }
+    }
+}
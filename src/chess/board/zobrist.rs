@@ -1,6 +1,10 @@
-use types::const_for;
+use types::{
+    Color, Move, MoveFlag,
+    PieceType::{Pawn, Rook},
+    Square, const_for,
+};
 
-use crate::util::XorShiftState;
+use crate::{chess::board::Board, util::XorShiftState};
 
 pub type Key = u64;
 
@@ -21,3 +25,72 @@ pub const PIECE: &[[[Key; 64]; 6]; 2] = &VALUES.0;
 pub const EN_PASSANT: &[Key; 8] = &VALUES.1;
 pub const CASTLING: &[Key; 16] = &VALUES.2;
 pub const SIDE: &Key = &VALUES.3;
+
+impl Board {
+    /// Zobrist key of the position after `mov` of `color`, without making the move
+    pub fn key_after(&self, mov: Move, color: Color) -> Key {
+        let (start, target, flag) = (mov.start(), mov.target(), mov.flag());
+        let piece = self.layout.unchecked_at(start);
+
+        let mut key = self.state.zobrist ^ SIDE;
+
+        if let Some(target) = self.state.en_passant {
+            key ^= EN_PASSANT[target.file()];
+        }
+
+        let mut castling = self.state.castling;
+        key ^= CASTLING[castling];
+        castling.remove(start, target);
+        key ^= CASTLING[castling];
+
+        match flag {
+            MoveFlag::DOUBLE_PAWN => key ^= EN_PASSANT[start.file()],
+            MoveFlag::KING_CASTLE => {
+                key ^= PIECE[color][Rook][Self::KING_CASTLE_START[color]]
+                    ^ PIECE[color][Rook][Self::KING_CASTLE_TARGET[color]];
+            }
+            MoveFlag::QUEEN_CASTLE => {
+                key ^= PIECE[color][Rook][Self::QUEEN_CASTLE_START[color]]
+                    ^ PIECE[color][Rook][Self::QUEEN_CASTLE_TARGET[color]];
+            }
+            MoveFlag::EN_PASSANT => {
+                let sq = Square::from(target.file(), Self::EN_PASSANT_CAPTURE[!color]);
+                key ^= PIECE[!color][Pawn][sq];
+            }
+            _ => {}
+        }
+
+        // Both captures and capturing promotions remove their piece from the target square
+        if self.layout.all().is_set(target) {
+            key ^= PIECE[!color][self.layout.unchecked_at(target)][target];
+        }
+
+        key ^ PIECE[color][piece][start] ^ PIECE[color][flag.piece().unwrap_or(piece)][target]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        CORPUS, FEN,
+        chess::{All, MoveList, Position},
+    };
+
+    #[test]
+    fn key_after() {
+        for fen in FEN.iter().chain(CORPUS.iter()) {
+            let mut pos = Position::from_fen(fen).unwrap();
+
+            let mut moves = MoveList::new();
+            pos.generate_legal::<All>(&mut moves);
+
+            for mov in moves.iter() {
+                let key = pos.key_after(mov);
+
+                pos.make_move(mov);
+                assert_eq!(key, pos.zobrist(), "{} {}", fen, mov);
+                pos.unmake_move(mov);
+            }
+        }
+    }
+}
@@ -39,6 +39,12 @@ impl MoveList {
     }
 }
 
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Deref for MoveList {
     type Target = [MoveListEntry];
 
@@ -9,7 +9,7 @@ use types::{Color, Move};
 
 use crate::{
     chess::{
-        MoveList,
+        All, MoveList,
         board::{Board, GenerationType, Key, PieceLayout},
     },
     error::Error,
@@ -43,6 +43,24 @@ impl Position {
         })
     }
 
+    /// Mirror the position vertically and swap the colors, so the evaluation stays the same
+    pub fn flip(&self) -> Self {
+        let stm = !self.stm;
+
+        Position {
+            board: self.board.flip(stm),
+            stm,
+            // We keep the fullmove counter
+            ply: if stm == Color::White {
+                self.ply - 1
+            } else {
+                self.ply + 1
+            },
+            height: 0,
+            history: Vec::new(),
+        }
+    }
+
     pub fn fen(&self) -> Result<String, std::fmt::Error> {
         self.board.fen(self.stm, (self.ply + 2) / 2)
     }
@@ -55,6 +73,10 @@ impl Position {
         &self.board.layout
     }
 
+    pub(crate) fn state(&self) -> &GameState {
+        &self.board.state
+    }
+
     pub fn zobrist(&self) -> Key {
         self.board.state.zobrist
     }
@@ -71,6 +93,25 @@ impl Position {
         self.board.generate::<TYPE>(moves, self.stm);
     }
 
+    pub fn generate_legal<TYPE: GenerationType>(&self, moves: &mut MoveList) {
+        self.board.generate_legal::<TYPE>(moves, self.stm);
+    }
+
+    pub fn legal_move_count(&self) -> usize {
+        let mut moves = MoveList::new();
+        self.generate_legal::<All>(&mut moves);
+
+        moves.len()
+    }
+
+    pub fn is_checkmate(&self) -> bool {
+        self.check() && self.legal_move_count() == 0
+    }
+
+    pub fn is_stalemate(&self) -> bool {
+        !self.check() && self.legal_move_count() == 0
+    }
+
     pub fn pseudo_legal(&self, mov: Move) -> bool {
         self.board.pseudo_legal(mov, self.stm)
     }
@@ -79,6 +120,20 @@ impl Position {
         self.board.legal(mov, self.stm)
     }
 
+    pub fn gives_check(&self, mov: Move) -> bool {
+        self.board.gives_check(mov, self.stm)
+    }
+
+    /// Static exchange evaluation of `mov`, which is true, if we win at least `threshold`
+    pub fn see(&self, mov: Move, threshold: i32) -> bool {
+        self.board.see(mov, self.stm, threshold)
+    }
+
+    /// Zobrist key of the position after `mov`, without making the move
+    pub fn key_after(&self, mov: Move) -> Key {
+        self.board.key_after(mov, self.stm)
+    }
+
     pub fn make_move(&mut self, mov: Move) {
         self.history.push(self.board.state.clone());
 
@@ -100,11 +155,78 @@ impl Position {
             .unmake_move(mov, self.stm, self.history.pop().unwrap());
     }
 
+    #[cfg(test)]
+    pub(crate) fn make_null_move(&mut self) {
+        self.history.push(self.board.state.clone());
+
+        self.board.make_null_move(self.stm);
+
+        self.stm = !self.stm;
+        self.ply += 1;
+        self.height += 1;
+    }
+
+    #[cfg(test)]
+    pub(crate) fn unmake_null_move(&mut self) {
+        debug_assert!(!self.history.is_empty());
+
+        self.stm = !self.stm;
+        self.ply -= 1;
+        self.height -= 1;
+
+        self.board.state = self.history.pop().unwrap();
+    }
+
     pub fn check(&self) -> bool {
         !self.board.state.checkers.is_empty()
     }
 
     pub fn draw(&self) -> bool {
-        self.board.draw() || (self.repetition() && self.ply != 0)
+        self.board.draw() || self.repetition()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FEN, chess::Position};
+
+    #[test]
+    fn flip() {
+        let pos = Position::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQq e3 0 3")
+            .unwrap();
+
+        assert_eq!(
+            "rnbqkbnr/pppp1ppp/8/3Pp3/8/8/PPP1PPPP/RNBQKBNR w Qkq e6 0 3",
+            pos.flip().fen().unwrap()
+        );
+
+        for fen in FEN {
+            let pos = Position::from_fen(fen).unwrap();
+            let flipped = Position::from_fen(&pos.flip().fen().unwrap()).unwrap();
+
+            assert_eq!(fen, pos.flip().flip().fen().unwrap());
+            assert_eq!(flipped.zobrist(), pos.flip().zobrist());
+        }
+    }
+
+    #[test]
+    fn terminal() {
+        const EXPECTED: [(&str, bool, bool); 4] = [
+            (
+                "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+                true,
+                false,
+            ),
+            ("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", false, true),
+            ("k7/1R6/1K6/8/8/8/8/8 b - - 0 1", false, true),
+            ("k7/8/1K6/8/8/8/8/7R w - - 0 1", false, false),
+        ];
+
+        for (fen, checkmate, stalemate) in EXPECTED {
+            let pos = Position::from_fen(fen).unwrap();
+
+            assert_eq!(checkmate, pos.is_checkmate(), "{}", fen);
+            assert_eq!(stalemate, pos.is_stalemate(), "{}", fen);
+        }
     }
 }
@@ -1,16 +1,16 @@
-use crate::chess::Position;
+use crate::chess::{
+    Position,
+    board::{BETWEEN, cuckoo},
+    position::GameState,
+};
 
 impl Position {
+    /// The current position occurred twice before, or once since the root of the search
     pub(crate) fn repetition(&self) -> bool {
         let mut repetitions = 0;
 
         for (distance, state) in self
-            .history
-            .iter()
-            .rev()
-            .enumerate()
-            // We have to consider all ply until the last irreversible move
-            .take(self.board.state.rule50_ply as usize)
+            .reversible()
             // A repetition can only happen two fullmoves ago
             .skip(3)
             // We only have to consider a position, where it is our turn
@@ -20,7 +20,8 @@ impl Position {
                 continue;
             }
 
-            if distance < self.ply {
+            // Within the search, we could just repeat the moves again
+            if distance < self.height {
                 return true;
             }
 
@@ -33,4 +34,127 @@ impl Position {
 
         false
     }
+
+    /// We can reach a position since the root of the search again with a single move
+    pub(crate) fn upcoming_repetition(&self) -> bool {
+        let occ = self.board.layout.all();
+
+        for (distance, state) in self.reversible().skip(2).step_by(2) {
+            // The earlier position has to be within the search
+            if distance + 1 >= self.height {
+                break;
+            }
+
+            let Some((a, b)) = cuckoo(self.board.state.zobrist ^ state.zobrist) else {
+                continue;
+            };
+
+            // The move is only possible, if nothing is in the way
+            if ((BETWEEN[a][b] - b.set()) & occ).is_empty() {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Earlier states by distance, which can be repeated, as neither an irreversible
+    /// nor a null move was made since
+    fn reversible(&self) -> impl Iterator<Item = (usize, &GameState)> {
+        let state = &self.board.state;
+        let plies = usize::from(state.rule50_ply).min(usize::from(state.plies_from_null));
+
+        self.history.iter().rev().enumerate().take(plies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        START_POS,
+        chess::{All, MoveList, Position},
+    };
+
+    fn play(pos: &mut Position, moves: &str) {
+        for str in moves.split_ascii_whitespace() {
+            let mut moves = MoveList::new();
+            pos.generate_legal::<All>(&mut moves);
+
+            let mov = moves.iter().find(|mov| format!("{}", mov) == str);
+            pos.make_move(mov.unwrap());
+        }
+    }
+
+    #[test]
+    fn threefold() {
+        let mut pos = Position::from_fen(START_POS).unwrap();
+
+        // The second occurrence in the game is not yet a draw
+        play(&mut pos, "g1f3 g8f6 f3g1 f6g8");
+        pos.reset_height();
+        assert!(!pos.repetition());
+
+        // The third one is, even at the root of the search
+        play(&mut pos, "g1f3 g8f6 f3g1 f6g8");
+        pos.reset_height();
+        assert!(pos.repetition());
+    }
+
+    #[test]
+    fn search_boundary() {
+        let mut pos = Position::from_fen(START_POS).unwrap();
+
+        play(&mut pos, "g1f3 g8f6");
+        pos.reset_height();
+
+        // The start position only occurred once before the root
+        play(&mut pos, "f3g1 f6g8");
+        assert!(!pos.repetition());
+
+        // The root occurred within the search
+        play(&mut pos, "g1f3 g8f6");
+        assert!(pos.repetition());
+    }
+
+    #[test]
+    fn null_move() {
+        let mut pos = Position::from_fen(START_POS).unwrap();
+        let zobrist = pos.zobrist();
+
+        // A null move is not a legal move, so no repetition can span it
+        play(&mut pos, "g1f3");
+        pos.make_null_move();
+        play(&mut pos, "f3g1");
+        pos.make_null_move();
+
+        assert_eq!(zobrist, pos.zobrist());
+        assert!(!pos.repetition());
+        assert!(!pos.upcoming_repetition());
+
+        pos.unmake_null_move();
+        pos.unmake_null_move();
+    }
+
+    #[test]
+    fn upcoming_repetition() {
+        let mut pos = Position::from_fen(START_POS).unwrap();
+
+        play(&mut pos, "b1c3 g8f6 g1f3 b8c6");
+        assert!(!pos.upcoming_repetition());
+
+        // Black can return its knight from c6
+        play(&mut pos, "f3g1");
+        assert!(pos.upcoming_repetition());
+
+        // White can return its rook to a1, unless the knight is in the way
+        for (fen, expected) in [
+            ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", true),
+            ("4k3/8/8/8/8/8/8/RN2K3 w - - 0 1", false),
+        ] {
+            let mut pos = Position::from_fen(fen).unwrap();
+
+            play(&mut pos, "e1e2 e8d8 a1a3 d8d7 a3c3 d7e7 c3c1 e7e8");
+            assert_eq!(expected, pos.upcoming_repetition(), "{}", fen);
+        }
+    }
 }
@@ -1,33 +1,79 @@
 use types::{Castling, Color, PieceType, Square, SquareSet};
 
-use crate::chess::board::{BETWEEN, Key, PieceLayout};
+use crate::chess::{
+    attacks,
+    board::{BETWEEN, Key, PieceLayout},
+};
 
 #[derive(Clone)]
 pub struct GameState {
     pub rule50_ply: u8,
+    /// Plies since the last null move, or since the start of the game
+    pub plies_from_null: u16,
     pub castling: Castling,
     pub en_passant: Option<Square>,
     pub capture: Option<PieceType>,
     /// Pieces, which block threats to our king
     pub blockers: SquareSet,
+    /// Our pieces, which give a discovered check when moving off the line to their king
+    pub discoverers: SquareSet,
     /// Pieces, which threaten our king
     pub checkers: SquareSet,
+    /// Squares, from which each of our piece types would check their king
+    pub check_squares: [SquareSet; 6],
     pub zobrist: Key,
+    /// Piece-square values, which are updated incrementally
+    pub midgame: [i32; 2],
+    pub endgame: [i32; 2],
+    pub phase: i32,
 }
 
 impl GameState {
     pub const EMPTY: Self = Self {
         rule50_ply: 0,
+        plies_from_null: 0,
         castling: Castling::EMPTY,
         en_passant: None,
         capture: None,
         blockers: SquareSet::EMPTY,
+        discoverers: SquareSet::EMPTY,
         checkers: SquareSet::EMPTY,
+        check_squares: [SquareSet::EMPTY; 6],
         zobrist: 0,
+        midgame: [0; 2],
+        endgame: [0; 2],
+        phase: 0,
     };
 
     pub fn set_blockers(&mut self, color: Color, layout: &PieceLayout) {
-        self.blockers = SquareSet::EMPTY;
+        self.blockers = Self::blockers(color, layout);
+
+        // Our pieces blocking our own sliders to their king can discover a check
+        self.discoverers = Self::blockers(!color, layout) & layout.color(color);
+    }
+
+    pub fn set_checkers(&mut self, color: Color, layout: &PieceLayout) {
+        self.checkers = layout.attackers(layout.king(color), color, layout.all());
+
+        let king = layout.king(!color);
+        let occ = layout.all();
+
+        let bishop = attacks::bishop(king, occ);
+        let rook = attacks::rook(king, occ);
+
+        self.check_squares = [
+            attacks::pawn(!color, king),
+            attacks::knight(king),
+            bishop,
+            rook,
+            bishop | rook,
+            SquareSet::EMPTY,
+        ];
+    }
+
+    /// All pieces, which are the only piece between a slider and the king of `color`
+    fn blockers(color: Color, layout: &PieceLayout) -> SquareSet {
+        let mut blockers = SquareSet::EMPTY;
 
         let king = layout.king(color);
 
@@ -42,12 +88,10 @@ impl GameState {
             // If we have no blocker our king is already in check, and
             // if we have more than one blocker, the sniper is not a threat
             if !blocker.is_empty() && blocker.is_less_two() {
-                self.blockers = self.blockers | blocker;
+                blockers = blockers | blocker;
             }
         }
-    }
 
-    pub fn set_checkers(&mut self, color: Color, layout: &PieceLayout) {
-        self.checkers = layout.attackers(layout.king(color), color, layout.all())
+        blockers
     }
 }
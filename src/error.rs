@@ -1,28 +1,62 @@
-use std::fmt::Display;
+use std::{fmt::Display, io};
 
 use crate::chess::FenParseError;
 
-pub type UciError = String;
-
 #[macro_export]
-macro_rules! syntax_error {
-    ($expected:expr, $found:expr) => {
-        format!("expected {}, but found {}", $expected, $found)
+macro_rules! unwrap_or {
+    ($result:expr) => {
+        $result.unwrap_or_else(|err| eprintln!("{}", err))
     };
 }
 
-#[macro_export]
-macro_rules! ok_or {
-    ($result:expr, $expected:expr, $found:expr) => {
-        $result.ok_or_else(|| syntax_error!($expected, $found))?
-    };
+/// The error type, which is returned from handling a command
+#[derive(Debug)]
+pub enum UciError {
+    /// The command doesn't match the expected syntax.
+    Syntax {
+        expected: &'static str,
+        found: String,
+    },
+    /// The key of the given name has no value.
+    MissingValue(String),
+    /// The value of the key can't be parsed.
+    InvalidValue { key: String, value: String },
+    /// The move is not legal in the current position.
+    IllegalMove(String),
+    /// The file at the given path can't be read or written.
+    Io(String, io::Error),
 }
 
-#[macro_export]
-macro_rules! unwrap_or {
-    ($result:expr) => {
-        $result.unwrap_or_else(|err| eprintln!("{}", err))
-    };
+impl Display for UciError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { expected, found } => {
+                write!(f, "expected {}, but found {}", expected, found)
+            }
+            Self::MissingValue(key) => write!(f, "missing value for {}", key),
+            Self::InvalidValue { key, value } => write!(f, "invalid value {} for {}", value, key),
+            Self::IllegalMove(mov) => write!(f, "illegal move {}", mov),
+            Self::Io(path, error) => write!(f, "unable to access {}: {}", path, error),
+        }
+    }
+}
+
+impl std::error::Error for UciError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl UciError {
+    pub fn syntax(expected: &'static str, found: impl Display) -> Self {
+        Self::Syntax {
+            expected,
+            found: found.to_string(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -40,10 +74,23 @@ impl Display for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Fen(error) => Some(error),
+            Error::Uci(error) => Some(error),
+        }
+    }
+}
 
 impl From<FenParseError> for Error {
     fn from(value: FenParseError) -> Self {
         Self::Fen(value)
     }
 }
+
+impl From<UciError> for Error {
+    fn from(value: UciError) -> Self {
+        Self::Uci(value)
+    }
+}
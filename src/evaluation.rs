@@ -1,38 +1,116 @@
+mod endgame;
 mod score;
 mod tables;
+mod terms;
+mod trace;
+mod wdl;
 
 pub use score::{DRAW, INF, MATE, mate_in, mated_in};
+pub use trace::trace;
+pub use wdl::{NORMALIZE_TO_PAWN, material, normalize, wdl};
 
+use endgame::SCALE_NORMAL;
 use tables::{ENDGAME_TABLE, MIDGAME_TABLE};
-use types::Color;
+use terms::{Context, TERMS};
+use types::{Color, PieceType, Square};
 
-use crate::chess::Position;
+use crate::chess::{PieceLayout, Position};
 
 const MIDGAME_VALUE: [i32; 6] = [82, 337, 365, 477, 1025, 0];
 const ENDGAME_VALUE: [i32; 6] = [94, 281, 297, 512, 936, 0];
 
-const PHASE: [i32; 6] = [0, 1, 1, 2, 4, 0];
+pub const PHASE: [i32; 6] = [0, 1, 1, 2, 4, 0];
+
+/// Midgame and endgame value of `piece` placed on `sq` for the given side
+pub fn psq(color: Color, piece: PieceType, sq: Square) -> (i32, i32) {
+    let sq = if color == Color::White { sq.flip() } else { sq };
+
+    (
+        MIDGAME_TABLE[piece][sq] + MIDGAME_VALUE[piece],
+        ENDGAME_TABLE[piece][sq] + ENDGAME_VALUE[piece],
+    )
+}
 
 pub fn evaluate(pos: &Position) -> i32 {
-    let (layout, stm) = (pos.layout(), pos.stm());
+    // Known endgames are evaluated without the general terms
+    if let Some(score) = endgame::specialized(pos) {
+        return score;
+    }
+
+    let (layout, stm, state) = (pos.layout(), pos.stm(), pos.state());
+
+    // The piece-square values are updated incrementally on each move
+    let (mut midgame, mut endgame, phase) = (state.midgame, state.endgame, state.phase);
+
+    debug_assert!((midgame, endgame, phase) == accumulate(layout));
+
+    let context = Context::new(layout);
+
+    for color in [Color::Black, Color::White] {
+        for (_, term) in TERMS {
+            let (mg, eg) = term(&context, color);
+
+            midgame[color] += mg;
+            endgame[color] += eg;
+        }
+    }
 
+    let scale = scale(layout, &endgame);
+
+    blend(
+        midgame[stm] - midgame[!stm],
+        endgame[stm] - endgame[!stm],
+        phase,
+        scale,
+    )
+}
+
+/// Scale factor for the side, which is ahead in the endgame
+fn scale(layout: &PieceLayout, endgame: &[i32; 2]) -> i32 {
+    let strong = match endgame[Color::White] >= endgame[Color::Black] {
+        true => Color::White,
+        false => Color::Black,
+    };
+
+    endgame::scale_factor(layout, strong)
+}
+
+/// Interpolate between the midgame and the scaled endgame score based on the game phase
+fn blend(midgame: i32, endgame: i32, phase: i32, scale: i32) -> i32 {
+    // Promotions can exceed the phase of the starting position
+    let phase = phase.min(24);
+
+    (midgame * phase + endgame * scale / SCALE_NORMAL * (24 - phase)) / 24
+}
+
+/// Computes the piece-square values from scratch
+fn accumulate(layout: &PieceLayout) -> ([i32; 2], [i32; 2], i32) {
     let (mut midgame, mut endgame, mut phase) = ([0, 0], [0, 0], 0);
 
     for color in [Color::Black, Color::White] {
         for sq in layout.color(color).iter() {
             let piece = layout.unchecked_at(sq);
+            let (mg, eg) = psq(color, piece, sq);
 
-            let sq = if color == Color::White { sq.flip() } else { sq };
-            midgame[color] += MIDGAME_TABLE[piece][sq] + MIDGAME_VALUE[piece];
-            endgame[color] += ENDGAME_TABLE[piece][sq] + ENDGAME_VALUE[piece];
+            midgame[color] += mg;
+            endgame[color] += eg;
 
             phase += PHASE[piece];
         }
     }
 
-    phase = phase.min(24);
+    (midgame, endgame, phase)
+}
 
-    debug_assert!(phase <= 24);
+#[cfg(test)]
+mod tests {
+    use crate::{CORPUS, FEN, chess::Position, evaluation::evaluate};
 
-    ((midgame[stm] - midgame[!stm]) * phase + (endgame[stm] - endgame[!stm]) * (24 - phase)) / 24
+    #[test]
+    fn symmetric() {
+        for fen in FEN.iter().chain(CORPUS.iter()) {
+            let pos = Position::from_fen(fen).unwrap();
+            assert_eq!(evaluate(&pos), evaluate(&pos.flip()), "{}", fen);
+        }
+    }
 }
@@ -0,0 +1,324 @@
+mod kpk;
+
+use types::{
+    Color, File,
+    PieceType::{Bishop, King, Knight, Pawn, Queen, Rook},
+    Rank, Square, SquareSet,
+};
+
+use crate::{
+    chess::{PieceLayout, Position},
+    evaluation::{DRAW, ENDGAME_VALUE},
+};
+
+pub const SCALE_NORMAL: i32 = 64;
+const SCALE_DRAW: i32 = 0;
+
+const KNOWN_WIN: i32 = 10_000;
+
+const PUSH_TO_EDGE: i32 = 20;
+const PUSH_TO_CORNER: i32 = 20;
+const PUSH_CLOSE: i32 = 10;
+
+const SCALE_OPPOSITE_BISHOPS: i32 = 18;
+const SCALE_DEFENDED_ROOK_PAWN: i32 = 12;
+const SCALE_ROOK_ENDGAME: i32 = 36;
+const SCALE_NO_PAWNS: i32 = 16;
+
+const LIGHT_SQUARES: SquareSet = SquareSet(0x55AA55AA55AA55AA);
+const QUEENSIDE: SquareSet = SquareSet(0x0F0F0F0F0F0F0F0F);
+const KINGSIDE: SquareSet = SquareSet(0xF0F0F0F0F0F0F0F0);
+
+/// Returns the score of the strong side, given the strong side and the side to move
+type Evaluator = fn(&PieceLayout, Color, Color) -> i32;
+
+const ENDGAMES: [([Signature; 2], Evaluator); 4] = [
+    (Signature::parse("KPK"), kpk),
+    (Signature::parse("KBNK"), kbnk),
+    (Signature::parse("KRK"), kxk),
+    (Signature::parse("KQK"), kxk),
+];
+
+/// Piece counts of both sides, which identify a material configuration
+#[derive(Clone, Copy, PartialEq)]
+struct Signature(u64);
+
+impl Signature {
+    fn new(layout: &PieceLayout) -> Self {
+        let mut signature = 0;
+
+        for color in [Color::White, Color::Black] {
+            for piece in [Pawn, Knight, Bishop, Rook, Queen] {
+                let count = (layout.get(piece) & layout.color(color)).popcnt() as u64;
+
+                signature |= count.min(15) << Self::shift(color as usize, piece as usize);
+            }
+        }
+
+        Self(signature)
+    }
+
+    /// Parse a code like `KBNK` for both possible strong sides
+    const fn parse(code: &str) -> [Self; 2] {
+        let (code, mut signatures) = (code.as_bytes(), [0; 2]);
+
+        let mut side = 0;
+        let mut i = 1;
+
+        while i < code.len() {
+            let piece = match code[i] {
+                b'P' => 0,
+                b'N' => 1,
+                b'B' => 2,
+                b'R' => 3,
+                b'Q' => 4,
+                _ => {
+                    side += 1;
+                    i += 1;
+
+                    continue;
+                }
+            };
+
+            signatures[0] += 1 << Self::shift(side, piece);
+            signatures[1] += 1 << Self::shift(side ^ 1, piece);
+
+            i += 1;
+        }
+
+        [Self(signatures[0]), Self(signatures[1])]
+    }
+
+    const fn shift(color: usize, piece: usize) -> usize {
+        (color * 5 + piece) * 4
+    }
+}
+
+/// Evaluate the position with a specialized evaluator, if the material configuration is known
+pub fn specialized(pos: &Position) -> Option<i32> {
+    let layout = pos.layout();
+
+    // All known configurations have at most four pieces
+    if layout.all().popcnt() > 4 {
+        return None;
+    }
+
+    let signature = Signature::new(layout);
+
+    for (signatures, evaluator) in ENDGAMES {
+        for strong in [Color::White, Color::Black] {
+            if signatures[strong] != signature {
+                continue;
+            }
+
+            let score = evaluator(layout, strong, pos.stm());
+
+            return Some(if pos.stm() == strong { score } else { -score });
+        }
+    }
+
+    None
+}
+
+/// Scale factor for the endgame score of the strong side, where [`SCALE_NORMAL`] leaves it unchanged
+pub fn scale_factor(layout: &PieceLayout, strong: Color) -> i32 {
+    let weak = !strong;
+
+    let pawns = [
+        layout.get(Pawn) & layout.color(Color::White),
+        layout.get(Pawn) & layout.color(Color::Black),
+    ];
+
+    let pieces = [
+        layout.color(Color::White) - layout.get(Pawn) - layout.get(King),
+        layout.color(Color::Black) - layout.get(Pawn) - layout.get(King),
+    ];
+
+    let bishops = layout.get(Bishop);
+
+    // Opposite colored bishops are drawish, even with a pawn or two more
+    if pieces[strong] == (bishops & pieces[strong])
+        && pieces[weak] == (bishops & pieces[weak])
+        && pieces[strong].popcnt() == 1
+        && pieces[weak].popcnt() == 1
+        && (bishops & LIGHT_SQUARES).popcnt() == 1
+    {
+        return SCALE_OPPOSITE_BISHOPS;
+    }
+
+    // Rook pawns can't be promoted if their king reaches the corner,
+    // and our bishop (if any) can't control the promotion square
+    if !pawns[strong].is_empty()
+        && pieces[weak].is_empty()
+        && (pieces[strong].is_empty() || pieces[strong] == (bishops & pieces[strong]))
+        && pieces[strong].is_less_two()
+    {
+        for file in [File::A, File::H] {
+            if !(pawns[strong] - file.set()).is_empty() {
+                continue;
+            }
+
+            let promotion = relative(Square::from(file, Rank::Eight), strong);
+            let wrong = (pieces[strong] & LIGHT_SQUARES).is_empty()
+                != (promotion.set() & LIGHT_SQUARES).is_empty();
+
+            if (pieces[strong].is_empty() || wrong) && layout.king(weak).distance(promotion) <= 1 {
+                return SCALE_DRAW;
+            }
+        }
+    }
+
+    let rooks = layout.get(Rook);
+
+    // Rook endgames with an equal number of pawns, or a pawn more are often drawn
+    if pieces[strong] == (rooks & pieces[strong])
+        && pieces[weak] == (rooks & pieces[weak])
+        && pieces[strong].popcnt() == 1
+        && pieces[weak].popcnt() == 1
+    {
+        let (ours, theirs) = (pawns[strong].popcnt(), pawns[weak].popcnt());
+
+        // Their king blocks our only pawn
+        if ours == 1 && theirs == 0 {
+            let pawn = relative(pawns[strong].iter().next().unwrap(), strong);
+            let king = relative(layout.king(weak), strong);
+
+            if king.file() == pawn.file() && king.rank() as u8 > pawn.rank() as u8 {
+                return SCALE_DEFENDED_ROOK_PAWN;
+            }
+        }
+
+        let all = pawns[strong] | pawns[weak];
+
+        if ours <= theirs + 1 && ((all - QUEENSIDE).is_empty() || (all - KINGSIDE).is_empty()) {
+            return SCALE_ROOK_ENDGAME;
+        }
+    }
+
+    // Without pawns, we need a significant material advantage to win
+    if pawns[strong].is_empty()
+        && material(layout, strong) - material(layout, weak) <= ENDGAME_VALUE[Bishop]
+    {
+        return SCALE_NO_PAWNS;
+    }
+
+    SCALE_NORMAL
+}
+
+fn kpk(layout: &PieceLayout, strong: Color, stm: Color) -> i32 {
+    let pawn = layout.get(Pawn).iter().next().unwrap();
+
+    if !kpk::win(strong, stm, layout.king(strong), pawn, layout.king(!strong)) {
+        return DRAW;
+    }
+
+    KNOWN_WIN + ENDGAME_VALUE[Pawn] + relative(pawn, strong).rank() as i32 * PUSH_CLOSE
+}
+
+/// Drive their king towards a corner of our bishop's color
+fn kbnk(layout: &PieceLayout, strong: Color, _: Color) -> i32 {
+    let (king, weak) = (layout.king(strong), layout.king(!strong));
+
+    let corners = match (layout.get(Bishop) & LIGHT_SQUARES).is_empty() {
+        true => [Square::A1, Square::H8],
+        false => [Square::A8, Square::H1],
+    };
+
+    let corner = corners
+        .map(|corner| manhattan(weak, corner))
+        .into_iter()
+        .min()
+        .unwrap();
+
+    KNOWN_WIN
+        + ENDGAME_VALUE[Knight]
+        + ENDGAME_VALUE[Bishop]
+        + PUSH_TO_CORNER * (14 - corner)
+        + PUSH_CLOSE * (7 - king.distance(weak) as i32)
+}
+
+/// Drive their lone king towards the edge of the board
+fn kxk(layout: &PieceLayout, strong: Color, _: Color) -> i32 {
+    let (king, weak) = (layout.king(strong), layout.king(!strong));
+
+    let file = weak.file() as i32;
+    let rank = weak.rank() as i32;
+
+    let edge = (3 - file).max(file - 4) + (3 - rank).max(rank - 4);
+
+    KNOWN_WIN
+        + material(layout, strong)
+        + PUSH_TO_EDGE * edge
+        + PUSH_CLOSE * (7 - king.distance(weak) as i32)
+}
+
+fn material(layout: &PieceLayout, color: Color) -> i32 {
+    [Knight, Bishop, Rook, Queen]
+        .iter()
+        .map(|&piece| {
+            (layout.get(piece) & layout.color(color)).popcnt() as i32 * ENDGAME_VALUE[piece]
+        })
+        .sum()
+}
+
+fn relative(sq: Square, color: Color) -> Square {
+    match color {
+        Color::White => sq,
+        Color::Black => sq.flip(),
+    }
+}
+
+fn manhattan(a: Square, b: Square) -> i32 {
+    (a.file() as i32 - b.file() as i32).abs() + (a.rank() as i32 - b.rank() as i32).abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use types::Color;
+
+    use crate::{
+        chess::Position,
+        evaluation::{
+            DRAW,
+            endgame::{SCALE_DRAW, scale_factor, specialized},
+        },
+    };
+
+    #[test]
+    fn kpk() {
+        const EXPECTED: [(&str, bool); 6] = [
+            ("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1", true),
+            ("4k3/4P3/4K3/8/8/8/8/8 b - - 0 1", false),
+            ("3k4/8/8/8/8/8/4P3/4K3 b - - 0 1", false),
+            ("8/8/8/8/8/8/k3P3/4K3 w - - 0 1", true),
+            ("8/8/8/8/4p3/4k3/8/4K3 b - - 0 1", true),
+            ("k7/8/8/8/8/8/P7/K7 w - - 0 1", false),
+        ];
+
+        for (fen, win) in EXPECTED {
+            let pos = Position::from_fen(fen).unwrap();
+            assert_eq!(win, specialized(&pos).unwrap() != DRAW, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn wrong_bishop() {
+        const EXPECTED: [(&str, Color, bool); 6] = [
+            // The bishop controls the promotion square of the rook pawn
+            ("1k6/8/8/3B4/P7/2K5/8/8 w - - 0 1", Color::White, false),
+            ("7k/8/6KP/8/8/8/8/2B5 w - - 0 1", Color::White, false),
+            ("8/8/8/8/p7/2k1b3/8/1K6 b - - 0 1", Color::Black, false),
+            // Their king reaches the corner, which our bishop can't control
+            ("1k6/8/8/8/P2B4/2K5/8/8 w - - 0 1", Color::White, true),
+            ("7k/8/6KP/8/8/8/8/1B6 w - - 0 1", Color::White, true),
+            ("8/8/8/8/p7/2k5/4b3/1K6 b - - 0 1", Color::Black, true),
+        ];
+
+        for (fen, strong, draw) in EXPECTED {
+            let pos = Position::from_fen(fen).unwrap();
+            let scale = scale_factor(pos.layout(), strong);
+
+            assert_eq!(draw, scale == SCALE_DRAW, "{}", fen);
+        }
+    }
+}
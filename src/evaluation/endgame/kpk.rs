@@ -0,0 +1,24 @@
+use types::{Color, File, Square};
+
+include!(concat!(env!("OUT_DIR"), "/kpk.rs"));
+
+/// Probe the king and pawn versus king bitbase, which is indexed from
+/// the perspective of white owning the pawn on files A to D
+pub fn win(strong: Color, stm: Color, king: Square, pawn: Square, weak: Square) -> bool {
+    let (mut king, mut pawn, mut weak) = match strong {
+        Color::White => (king, pawn, weak),
+        Color::Black => (king.flip(), pawn.flip(), weak.flip()),
+    };
+
+    if pawn.file() as u8 > File::D as u8 {
+        (king, pawn, weak) = (king.mirror(), pawn.mirror(), weak.mirror());
+    }
+
+    let index = king as usize
+        | (weak as usize) << 6
+        | usize::from(stm != strong) << 12
+        | (pawn.file() as usize) << 13
+        | (6 - pawn.rank() as usize) << 15;
+
+    KPK[index / 32] & (1 << (index % 32)) != 0
+}
@@ -18,6 +18,25 @@ pub const ENDGAME_TABLE: [&[i32; 64]; 6] = [
     &ENDGAME_KING_TABLE,
 ];
 
+// Indexed by the number of safe squares a piece attacks
+pub const MIDGAME_MOBILITY: [&[i32]; 6] = [
+    &[],
+    &MIDGAME_KNIGHT_MOBILITY,
+    &MIDGAME_BISHOP_MOBILITY,
+    &MIDGAME_ROOK_MOBILITY,
+    &MIDGAME_QUEEN_MOBILITY,
+    &[],
+];
+
+pub const ENDGAME_MOBILITY: [&[i32]; 6] = [
+    &[],
+    &ENDGAME_KNIGHT_MOBILITY,
+    &ENDGAME_BISHOP_MOBILITY,
+    &ENDGAME_ROOK_MOBILITY,
+    &ENDGAME_QUEEN_MOBILITY,
+    &[],
+];
+
 const MIDGAME_PAWN_TABLE: [i32; 64] = [
       0,   0,   0,   0,   0,   0,  0,   0,
      98, 134,  61,  95,  68, 126, 34, -11,
@@ -150,3 +169,36 @@ const ENDGAME_KING_TABLE: [i32; 64] = [
     -53, -34, -21, -11, -28, -14, -24, -43
 ];
 
+const MIDGAME_KNIGHT_MOBILITY: [i32; 9] = [
+    -30, -20, -8, -2, 3, 8, 13, 17, 20,
+];
+
+const ENDGAME_KNIGHT_MOBILITY: [i32; 9] = [
+    -40, -28, -14, -6, 2, 8, 12, 15, 17,
+];
+
+const MIDGAME_BISHOP_MOBILITY: [i32; 14] = [
+    -25, -14, -2, 6, 12, 18, 22, 25, 28, 30, 32, 34, 36, 38,
+];
+
+const ENDGAME_BISHOP_MOBILITY: [i32; 14] = [
+    -35, -20, -8, 2, 10, 16, 22, 26, 29, 31, 33, 35, 37, 39,
+];
+
+const MIDGAME_ROOK_MOBILITY: [i32; 15] = [
+    -20, -12, -6, -2, 0, 3, 6, 9, 12, 14, 16, 18, 20, 22, 24,
+];
+
+const ENDGAME_ROOK_MOBILITY: [i32; 15] = [
+    -40, -20, -6, 4, 12, 20, 28, 34, 38, 42, 46, 49, 52, 55, 57,
+];
+
+const MIDGAME_QUEEN_MOBILITY: [i32; 28] = [
+    -15, -10, -6, -3,  0,  2,  4,  6,  8, 10, 12, 13, 14, 15,
+     16,  17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29,
+];
+
+const ENDGAME_QUEEN_MOBILITY: [i32; 28] = [
+    -25, -18, -12, -7, -3,  0,  3,  6,  9, 12, 15, 18, 20, 22,
+     24,  26,  28, 30, 32, 34, 36, 38, 40, 41, 42, 43, 44, 45,
+];
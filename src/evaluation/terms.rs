@@ -0,0 +1,209 @@
+use types::{
+    Color,
+    PieceType::{Bishop, King, Knight, Pawn, Queen, Rook},
+    Square, SquareSet,
+};
+
+use crate::{
+    chess::{PieceLayout, attacks},
+    evaluation::tables::{ENDGAME_MOBILITY, MIDGAME_MOBILITY},
+};
+
+/// A single evaluation term, which returns the midgame and endgame score for the given side
+pub type Term = fn(&Context, Color) -> (i32, i32);
+
+pub const TERMS: [(&str, Term); 5] = [
+    ("Mobility", mobility),
+    ("King safety", king_safety),
+    ("Shelter", shelter),
+    ("Threats", threats),
+    ("Pieces", pieces),
+];
+
+// Weight of a single attacked king zone square by piece type
+const KING_ATTACK_WEIGHT: [i32; 6] = [0, 2, 2, 3, 5, 0];
+// A lone attacker is rarely dangerous, so we scale the attack by the number of attackers
+const KING_ATTACKER_SCALE: [i32; 8] = [0, 0, 50, 75, 88, 94, 97, 99];
+const KING_DANGER_LIMIT: i32 = 32;
+
+// Indexed by the distance of the closest pawn in front of our king, where zero means missing
+const MIDGAME_SHIELD: [i32; 4] = [-24, 18, 8, 0];
+const MIDGAME_STORM: [i32; 4] = [0, 6, 24, 12];
+
+const THREAT_BY_PAWN: (i32, i32) = (40, 30);
+const HANGING: (i32, i32) = (28, 16);
+
+const ROOK_OPEN_FILE: (i32, i32) = (30, 10);
+const ROOK_SEMI_OPEN_FILE: (i32, i32) = (14, 6);
+const BISHOP_PAIR: (i32, i32) = (26, 48);
+
+/// Attack information shared between all evaluation terms
+pub struct Context<'a> {
+    layout: &'a PieceLayout,
+    pawn_attacks: [SquareSet; 2],
+    attacks: [SquareSet; 2],
+    mobility: [(i32, i32); 2],
+    king_attackers: [usize; 2],
+    king_units: [i32; 2],
+}
+
+impl<'a> Context<'a> {
+    pub fn new(layout: &'a PieceLayout) -> Self {
+        let mut context = Self {
+            layout,
+            pawn_attacks: [SquareSet::EMPTY; 2],
+            attacks: [SquareSet::EMPTY; 2],
+            mobility: [(0, 0); 2],
+            king_attackers: [0; 2],
+            king_units: [0; 2],
+        };
+
+        for color in [Color::White, Color::Black] {
+            for sq in (layout.get(Pawn) & layout.color(color)).iter() {
+                context.pawn_attacks[color] =
+                    context.pawn_attacks[color] | attacks::pawn(color, sq);
+            }
+
+            context.attacks[color] =
+                context.pawn_attacks[color] | attacks::king(layout.king(color));
+        }
+
+        let occ = layout.all();
+
+        for color in [Color::White, Color::Black] {
+            // We don't count squares occupied by our pieces or attacked by their pawns
+            let area = !(layout.color(color) | context.pawn_attacks[!color]);
+            let zone = attacks::king(layout.king(!color)) | layout.king(!color).set();
+
+            for piece in [Knight, Bishop, Rook, Queen] {
+                for sq in (layout.get(piece) & layout.color(color)).iter() {
+                    let set = attacks::by_type(piece, sq, occ);
+
+                    context.attacks[color] = context.attacks[color] | set;
+
+                    let count = (set & area).popcnt() as usize;
+                    context.mobility[color].0 += MIDGAME_MOBILITY[piece][count];
+                    context.mobility[color].1 += ENDGAME_MOBILITY[piece][count];
+
+                    let hits = (set & zone).popcnt() as i32;
+                    if hits != 0 {
+                        context.king_attackers[color] += 1;
+                        context.king_units[color] += KING_ATTACK_WEIGHT[piece] * hits;
+                    }
+                }
+            }
+        }
+
+        context
+    }
+}
+
+pub fn mobility(context: &Context, color: Color) -> (i32, i32) {
+    context.mobility[color]
+}
+
+pub fn king_safety(context: &Context, color: Color) -> (i32, i32) {
+    let attackers = context.king_attackers[!color].min(KING_ATTACKER_SCALE.len() - 1);
+    let danger =
+        (context.king_units[!color] * KING_ATTACKER_SCALE[attackers] / 100).min(KING_DANGER_LIMIT);
+
+    (-danger * danger / 2, -danger)
+}
+
+pub fn shelter(context: &Context, color: Color) -> (i32, i32) {
+    let layout = context.layout;
+    let king = layout.king(color);
+
+    let ours = layout.get(Pawn) & layout.color(color);
+    let theirs = layout.get(Pawn) & layout.color(!color);
+
+    // All squares on the ranks in front of our king
+    let rank = king.rank() as u32;
+    let front = SquareSet(match color {
+        Color::White => u64::MAX.checked_shl(8 * (rank + 1)).unwrap_or(0),
+        Color::Black => (1 << (8 * rank)) - 1,
+    });
+
+    let mut midgame = 0;
+
+    for delta in -1..=1 {
+        let Some(file) = king.file().try_delta(delta) else {
+            continue;
+        };
+
+        let mask = file.set() & front;
+
+        midgame += match closest(ours & mask, king, color) {
+            Some(distance) => MIDGAME_SHIELD[distance.min(3)],
+            None => MIDGAME_SHIELD[0],
+        };
+
+        if let Some(distance) = closest(theirs & mask, king, color) {
+            midgame -= MIDGAME_STORM[distance.min(3)];
+        }
+    }
+
+    (midgame, 0)
+}
+
+pub fn threats(context: &Context, color: Color) -> (i32, i32) {
+    let layout = context.layout;
+    let them = layout.color(!color) - layout.get(King);
+
+    // Their pieces attacked by our pawns
+    let by_pawn = ((them - layout.get(Pawn)) & context.pawn_attacks[color]).popcnt() as i32;
+    // Their pieces attacked by us, which they don't defend
+    let hanging = ((them & context.attacks[color]) - context.attacks[!color]).popcnt() as i32;
+
+    (
+        by_pawn * THREAT_BY_PAWN.0 + hanging * HANGING.0,
+        by_pawn * THREAT_BY_PAWN.1 + hanging * HANGING.1,
+    )
+}
+
+pub fn pieces(context: &Context, color: Color) -> (i32, i32) {
+    let layout = context.layout;
+
+    let pawns = layout.get(Pawn);
+    let ours = pawns & layout.color(color);
+
+    let (mut midgame, mut endgame) = (0, 0);
+
+    for sq in (layout.get(Rook) & layout.color(color)).iter() {
+        let file = sq.file().set();
+
+        let bonus = if (file & pawns).is_empty() {
+            ROOK_OPEN_FILE
+        } else if (file & ours).is_empty() {
+            ROOK_SEMI_OPEN_FILE
+        } else {
+            (0, 0)
+        };
+
+        midgame += bonus.0;
+        endgame += bonus.1;
+    }
+
+    if !(layout.get(Bishop) & layout.color(color)).is_less_two() {
+        midgame += BISHOP_PAIR.0;
+        endgame += BISHOP_PAIR.1;
+    }
+
+    (midgame, endgame)
+}
+
+/// Rank distance between our king and the closest pawn in `set`
+fn closest(set: SquareSet, king: Square, color: Color) -> Option<usize> {
+    if set.is_empty() {
+        return None;
+    }
+
+    let index = match color {
+        Color::White => set.index_lsb(),
+        Color::Black => set.index_msb(),
+    };
+
+    let sq = Square::new(index).unwrap();
+
+    Some((sq.rank() as usize).abs_diff(king.rank() as usize))
+}
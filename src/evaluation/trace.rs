@@ -0,0 +1,141 @@
+use std::fmt::Write;
+
+use types::{Color, File, Rank, Square};
+
+use crate::{
+    chess::Position,
+    evaluation::{
+        accumulate, blend, endgame,
+        endgame::SCALE_NORMAL,
+        evaluate, psq, scale,
+        terms::{Context, TERMS},
+    },
+};
+
+/// Breakdown of all evaluation terms from the perspective of white
+pub fn trace(pos: &Position) -> Result<String, std::fmt::Error> {
+    const HEADER: &str = concat!(
+        "         Term |    White    |    Black    |    Total",
+        '\n',
+        "              |   MG    EG  |   MG    EG  |   MG    EG",
+        '\n',
+    );
+    const DELIMITER: &str = concat!(
+        "--------------+-------------+-------------+-------------",
+        '\n'
+    );
+
+    let layout = pos.layout();
+    let mut trace = String::from(HEADER);
+
+    let (mut midgame, mut endgame, phase) = accumulate(layout);
+
+    write!(trace, "{}", DELIMITER)?;
+    row(&mut trace, "Material", midgame, endgame)?;
+
+    let context = Context::new(layout);
+
+    for (name, term) in TERMS {
+        let [white, black] = [Color::White, Color::Black].map(|color| term(&context, color));
+
+        row(&mut trace, name, [white.0, black.0], [white.1, black.1])?;
+
+        for (color, (mg, eg)) in [(Color::White, white), (Color::Black, black)] {
+            midgame[color] += mg;
+            endgame[color] += eg;
+        }
+    }
+
+    write!(trace, "{}", DELIMITER)?;
+    row(&mut trace, "Total", midgame, endgame)?;
+
+    let scale = scale(layout, &endgame);
+    let midgame = midgame[Color::White] - midgame[Color::Black];
+    let endgame = endgame[Color::White] - endgame[Color::Black];
+
+    write!(trace, "\nPhase: {} / 24", phase.min(24))?;
+    write!(trace, "\nScale: {} / {}", scale, SCALE_NORMAL)?;
+    write!(
+        trace,
+        "\nBlended: {}",
+        blend(midgame, endgame, phase, scale)
+    )?;
+
+    if let Some(score) = endgame::specialized(pos) {
+        write!(trace, "\nSpecialized: {}", relative(score, pos.stm()))?;
+    }
+
+    let score = relative(evaluate(pos), pos.stm());
+
+    write!(trace, "\nFinal evaluation: {} (white side)\n\n", score)?;
+    write!(trace, "{}", board(pos, phase)?)?;
+
+    Ok(trace)
+}
+
+fn row(trace: &mut String, name: &str, midgame: [i32; 2], endgame: [i32; 2]) -> std::fmt::Result {
+    let (white, black) = (Color::White, Color::Black);
+
+    writeln!(
+        trace,
+        "{:>13} | {:>5} {:>5} | {:>5} {:>5} | {:>5} {:>5}",
+        name,
+        midgame[white],
+        endgame[white],
+        midgame[black],
+        endgame[black],
+        midgame[white] - midgame[black],
+        endgame[white] - endgame[black],
+    )
+}
+
+/// Tapered piece-square value of each piece from the perspective of white
+fn board(pos: &Position, phase: i32) -> Result<String, std::fmt::Error> {
+    const DELIMITER: &str = concat!(
+        "+-------+-------+-------+-------+-------+-------+-------+-------+",
+        '\n'
+    );
+
+    let layout = pos.layout();
+    let mut board = String::from(DELIMITER);
+
+    for rank in Rank::iter().rev() {
+        for file in File::iter() {
+            let piece = layout.at(Square::from(file, rank));
+            write!(board, "|   {}   ", piece.map(char::from).unwrap_or(' '))?;
+        }
+
+        writeln!(board, "|")?;
+
+        for file in File::iter() {
+            let sq = Square::from(file, rank);
+
+            let Some(piece) = layout.at(sq) else {
+                write!(board, "|       ")?;
+                continue;
+            };
+
+            let (mg, eg) = psq(piece.color(), piece.typ(), sq);
+            let value = blend(mg, eg, phase, SCALE_NORMAL);
+
+            write!(board, "| {:>5} ", relative(value, piece.color()))?;
+        }
+
+        write!(board, "| {}\n{}", rank, DELIMITER)?;
+    }
+
+    writeln!(
+        board,
+        "    a       b       c       d       e       f       g       h"
+    )?;
+
+    Ok(board)
+}
+
+/// Convert `score` of the given side to the perspective of white
+fn relative(score: i32, color: Color) -> i32 {
+    match color {
+        Color::White => score,
+        Color::Black => -score,
+    }
+}
@@ -0,0 +1,51 @@
+#![feature(adt_const_params)]
+
+//! Chess engine library, which exposes position setup, move generation, search and evaluation.
+//! The UCI binary is a thin consumer of [`uci::run`].
+
+mod chess;
+mod error;
+mod evaluation;
+mod search;
+pub mod uci;
+mod util;
+
+pub use chess::{
+    All, Capture, FenParseError, GenerationType, MoveList, PieceLayout, Position, Quiet,
+    ValidationError,
+};
+pub use error::{Error, UciError};
+pub use evaluation::{evaluate, trace};
+pub use search::{
+    Bound, Collector, Iteration, SearchEvent, SearchLimit, SearchObserver, Silent,
+    TranspositionTable, go,
+};
+pub use types::{Color, Move, MoveFlag, Piece, PieceType, Square, SquareSet};
+pub use util::{Divide, perft};
+
+/// FEN of the initial position of a game
+pub const START_POS: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+const FEN: [&str; 6] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+];
+
+// Additional positions with asymmetric castling rights, en passant and known endgames
+#[cfg(test)]
+const CORPUS: [&str; 10] = [
+    "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQq e3 0 3",
+    "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP3PPP/R2QKB1R w KQ - 0 9",
+    "2r3k1/5ppp/p3p3/1p1pP3/3P1P2/P1R3P1/1P4KP/8 b - - 0 30",
+    "8/5pk1/6p1/2b5/4B3/6P1/5PK1/8 w - - 0 40",
+    "8/8/4k3/8/2R5/8/4P3/4K2r w - - 0 50",
+    "7k/8/6KP/8/8/8/8/1B6 w - - 0 60",
+    "8/8/8/8/8/4k3/4p3/4K3 w - - 0 70",
+    "8/8/8/3k4/8/8/8/1NB1K3 b - - 0 70",
+    "6k1/5p1p/6p1/8/8/8/q4PPP/1R4K1 w - - 0 25",
+    "r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1",
+];
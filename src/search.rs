@@ -1,5 +1,6 @@
 mod history;
 mod limit;
+mod observer;
 mod picker;
 mod pv;
 mod quiescence;
@@ -7,15 +8,15 @@ mod transposition;
 mod worker;
 
 pub use limit::SearchLimit;
-pub use transposition::TranspositionTable;
+pub use observer::{Collector, Iteration, SearchEvent, SearchObserver, Silent};
+pub use transposition::{Bound, TranspositionTable};
 
-use std::{iter, sync::atomic::AtomicBool};
+use std::sync::atomic::AtomicBool;
 
 use crate::{
     chess::Position,
-    evaluation::{INF, MATE},
+    evaluation::{DRAW, INF, MATE, mated_in},
     search::{
-        picker::MovePicker,
         pv::{PrincipalVariation, pvs},
         quiescence::quiescence,
         worker::Worker,
@@ -53,34 +54,52 @@ impl NodeType for NonPV {
     const ROOT: bool = false;
 }
 
+/// Search `pos` within `limits` until done or aborted, where `observer` receives the progress
+/// of the search. Returns the score and best move, if any move is legal.
 pub fn go(
     pos: &Position,
     limits: &SearchLimit,
     tt: &TranspositionTable,
     abort: &AtomicBool,
+    observer: &dyn SearchObserver,
 ) -> (i32, Option<Move>) {
-    let mut main = Worker::new(pos.clone(), tt.view(), limits.clone(), &abort, true);
+    let mut main = Worker::new(
+        pos.clone(),
+        tt.view(),
+        limits.clone(),
+        abort,
+        observer,
+        true,
+    );
 
     main.pos.reset_height();
 
+    // Without a legal move, there is nothing to search
+    if pos.legal_move_count() == 0 {
+        let score = if pos.check() { mated_in(0) } else { DRAW };
+        main.report_best_move(None);
+
+        return (score, None);
+    }
+
     iterative_deepening(&mut main, limits.depth as i32);
 
     let (score, mov) = main.result();
 
-    if mov.is_some() {
-        return (score, mov);
-    }
+    debug_assert!(mov.is_some());
 
-    let mut picker = MovePicker::new(None);
-    let mov = iter::from_fn(|| picker.next(&main)).find(|&mov| main.pos.legal(mov));
+    main.report_best_move(mov);
 
-    (-INF, mov)
+    (score, mov)
 }
 
 fn iterative_deepening(worker: &mut Worker, max_depth: i32) {
     let mut pv = PrincipalVariation::EMPTY;
 
-    for depth in 1..=max_depth.min(MAX_PLY) {
+    // The first iteration can't be aborted, so we search it even without a depth limit
+    for depth in 1..=max_depth.clamp(1, MAX_PLY) {
+        worker.reset_seldepth();
+
         let score = pvs::<Root>(worker, &mut pv, -INF, INF, depth);
 
         // We only consider finished iterations
@@ -88,7 +107,7 @@ fn iterative_deepening(worker: &mut Worker, max_depth: i32) {
             break;
         }
 
-        worker.update_pv(&pv);
+        worker.update_pv(depth, &pv);
         worker.report(depth);
 
         // We can skip further search if we found a forced mate
@@ -97,3 +116,108 @@ fn iterative_deepening(worker: &mut Worker, max_depth: i32) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use types::Move;
+
+    use crate::{
+        CORPUS, FEN,
+        chess::{All, MoveList, Position},
+        search::{Collector, SearchEvent, SearchLimit, Silent, TranspositionTable, go},
+    };
+
+    #[test]
+    fn mirror() {
+        let mut tt = TranspositionTable::new();
+        let abort = AtomicBool::new(false);
+
+        let mut limits = SearchLimit::MAX;
+        limits.depth = 5;
+
+        // The keys of both positions differ, so a small table would make them collide differently
+        tt.resize(16);
+
+        for fen in FEN.iter().chain(CORPUS.iter()) {
+            let pos = Position::from_fen(fen).unwrap();
+
+            tt.clear();
+            let (score, mov) = go(&pos, &limits, &tt, &abort, &Silent);
+
+            tt.clear();
+            let (flipped_score, flipped_mov) = go(&pos.flip(), &limits, &tt, &abort, &Silent);
+
+            let flip = |mov: Move| Move::new(mov.start().flip(), mov.target().flip(), mov.flag());
+
+            assert_eq!(score, flipped_score, "{}", fen);
+            assert_eq!(
+                mov.map(|mov| mov.inner()),
+                flipped_mov.map(|mov| flip(mov).inner()),
+                "{}",
+                fen
+            );
+        }
+    }
+
+    #[test]
+    fn aborted() {
+        let mut tt = TranspositionTable::new();
+        tt.resize(1);
+
+        // Even if we are stopped right away, the first iteration gives us a legal move
+        let abort = AtomicBool::new(true);
+
+        for fen in FEN.iter().chain(CORPUS.iter()) {
+            let pos = Position::from_fen(fen).unwrap();
+            let (_, mov) = go(&pos, &SearchLimit::MAX, &tt, &abort, &Silent);
+
+            let mut moves = MoveList::new();
+            pos.generate_legal::<All>(&mut moves);
+
+            assert_eq!(!moves.is_empty(), mov.is_some(), "{}", fen);
+            assert!(mov.is_none_or(|mov| moves.iter().any(|other| other.inner() == mov.inner())));
+        }
+    }
+
+    #[test]
+    fn observer() {
+        let mut tt = TranspositionTable::new();
+        let abort = AtomicBool::new(false);
+        let collector = Collector::default();
+
+        let mut limits = SearchLimit::MAX;
+        limits.depth = 4;
+
+        tt.resize(1);
+
+        let pos = Position::from_fen(FEN[0]).unwrap();
+        let (_, mov) = go(&pos, &limits, &tt, &abort, &collector);
+
+        let events = collector.events();
+        let mut depth = 0;
+
+        for event in events.iter() {
+            match event {
+                SearchEvent::CurrentMove(current, _, number) => {
+                    assert_eq!(depth + 1, *current);
+                    assert!((1..=20).contains(number));
+                }
+                SearchEvent::Iteration(iteration) => {
+                    depth += 1;
+
+                    assert_eq!(depth, iteration.depth);
+                    assert!(!iteration.pv.is_empty());
+                }
+                _ => {}
+            }
+        }
+
+        assert_eq!(limits.depth as i32, depth);
+        assert!(matches!(
+            events.last(),
+            Some(SearchEvent::BestMove(Some(best))) if best.inner() == mov.unwrap().inner()
+        ));
+    }
+}
@@ -16,7 +16,11 @@ impl SearchLimit {
         time: u128::MAX,
     };
 
-    pub fn check(&self, elapsed: u128, nodes: u64) -> bool {
-        elapsed > self.time || nodes > self.nodes
+    pub fn exceeds_time(&self, elapsed: u128) -> bool {
+        elapsed > self.time
+    }
+
+    pub fn exceeds_nodes(&self, nodes: u64) -> bool {
+        nodes > self.nodes
     }
 }
@@ -0,0 +1,83 @@
+use std::sync::Mutex;
+
+use types::Move;
+
+use crate::search::transposition::Bound;
+
+/// Snapshot of the search, which is passed to a [`SearchObserver`]
+#[derive(Clone)]
+pub struct Iteration {
+    pub depth: i32,
+    /// Maximum height reached in the iteration, including quiescence search
+    pub seldepth: usize,
+    pub score: i32,
+    pub nodes: u64,
+    /// Elapsed time since the start of the search in milliseconds
+    pub time: u128,
+    /// Occupied entries of the transposition table per mille
+    pub hashfull: usize,
+    pub pv: Vec<Move>,
+}
+
+/// Receives the progress of the main search thread, where all events are optional
+pub trait SearchObserver {
+    /// An iteration of the given depth was finished.
+    fn iteration(&self, _iteration: &Iteration) {}
+
+    /// We start searching the root move with the given number, starting from one.
+    fn current_move(&self, _depth: i32, _mov: Move, _number: usize) {}
+
+    /// The root score changed within an unfinished iteration.
+    fn bound(&self, _iteration: &Iteration, _bound: Bound) {}
+
+    /// The search is done, where we have no move if there is no legal move.
+    fn best_move(&self, _mov: Option<Move>) {}
+}
+
+/// A single event received by [`Collector`]
+#[derive(Clone)]
+pub enum SearchEvent {
+    Iteration(Iteration),
+    CurrentMove(i32, Move, usize),
+    Bound(Iteration, Bound),
+    BestMove(Option<Move>),
+}
+
+/// Observer, which stores all events in memory
+#[derive(Default)]
+pub struct Collector {
+    events: Mutex<Vec<SearchEvent>>,
+}
+
+impl Collector {
+    pub fn events(&self) -> Vec<SearchEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    fn push(&self, event: SearchEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl SearchObserver for Collector {
+    fn iteration(&self, iteration: &Iteration) {
+        self.push(SearchEvent::Iteration(iteration.clone()));
+    }
+
+    fn current_move(&self, depth: i32, mov: Move, number: usize) {
+        self.push(SearchEvent::CurrentMove(depth, mov, number));
+    }
+
+    fn bound(&self, iteration: &Iteration, bound: Bound) {
+        self.push(SearchEvent::Bound(iteration.clone(), bound));
+    }
+
+    fn best_move(&self, mov: Option<Move>) {
+        self.push(SearchEvent::BestMove(mov));
+    }
+}
+
+/// Observer, which ignores all events
+pub struct Silent;
+
+impl SearchObserver for Silent {}
@@ -1,4 +1,6 @@
-use types::{Move, MoveFlag, PieceType};
+use std::cmp::Reverse;
+
+use types::{Color, Move, MoveFlag, PieceType};
 
 use crate::{
     chess::{Capture, GenerationType, MoveList, MoveListEntry, PieceLayout, Quiet},
@@ -51,9 +53,8 @@ impl MovePicker {
         }
 
         if self.stage == Stage::YieldCaptures {
-            if let Some(entry) = self.moves.get(self.index) {
-                self.index += 1;
-                return Some(entry.mov);
+            if let Some(mov) = self.yield_next() {
+                return Some(mov);
             }
 
             self.stage = Stage::GenerateQuiets;
@@ -70,9 +71,8 @@ impl MovePicker {
         }
 
         if self.stage == Stage::YieldQuiets {
-            if let Some(entry) = self.moves.get(self.index) {
-                self.index += 1;
-                return Some(entry.mov);
+            if let Some(mov) = self.yield_next() {
+                return Some(mov);
             }
 
             self.stage = Stage::Done;
@@ -81,6 +81,19 @@ impl MovePicker {
         None
     }
 
+    // We already returned the transposition move first, so we skip it here
+    fn yield_next(&mut self) -> Option<Move> {
+        while let Some(entry) = self.moves.get(self.index) {
+            self.index += 1;
+
+            if self.tt.is_none_or(|tt| tt.inner() != entry.mov.inner()) {
+                return Some(entry.mov);
+            }
+        }
+
+        None
+    }
+
     pub fn set_quiet(&mut self, quiet: bool) {
         self.quiet = quiet;
     }
@@ -123,6 +136,56 @@ impl MovePicker {
             MovePicker::score_quiets(worker, &mut self.moves[self.index..]);
         }
 
-        self.moves[self.index..].sort_unstable_by(|a, b| b.score.cmp(&a.score));
+        // Ties are broken by the squares from our point of view, so the order
+        // of the moves doesn't depend on which color we are
+        let flip = if worker.pos.stm() == Color::White {
+            0
+        } else {
+            0b111_000
+        };
+
+        self.moves[self.index..].sort_unstable_by_key(|entry| {
+            let (start, target) = (entry.mov.start() as u8, entry.mov.target() as u8);
+            (Reverse(entry.score), start ^ flip, target ^ flip)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use crate::{
+        FEN,
+        chess::{All, MoveList, Position},
+        search::{SearchLimit, Silent, TranspositionTable, picker::MovePicker, worker::Worker},
+    };
+
+    #[test]
+    fn unique() {
+        let tt = TranspositionTable::new();
+        let abort = AtomicBool::new(false);
+
+        for fen in FEN {
+            let pos = Position::from_fen(fen).unwrap();
+
+            let mut moves = MoveList::new();
+            pos.generate::<All>(&mut moves);
+
+            let worker = Worker::new(pos, tt.view(), SearchLimit::MAX, &abort, &Silent, true);
+
+            // The transposition move comes first, and isn't repeated by the later stages
+            for tt_move in moves.iter() {
+                let mut picker = MovePicker::new(Some(tt_move));
+                let mut picked = Vec::new();
+
+                while let Some(mov) = picker.next(&worker) {
+                    picked.push(mov.inner());
+                }
+
+                assert_eq!(picked[0], tt_move.inner(), "{}", fen);
+                assert_eq!(picked.len(), moves.len(), "{}", fen);
+            }
+        }
     }
 }
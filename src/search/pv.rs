@@ -4,13 +4,21 @@ use types::{ArrayVec, Move};
 
 use crate::{
     chess::{All, MoveList},
-    evaluation::{DRAW, INF, mate_in, mated_in},
+    evaluation::{DRAW, INF, MATE, evaluate, mate_in, mated_in},
     search::{
         MAX_DEPTH, MAX_PLY, NodeType, NonPV, PV, picker::MovePicker, quiescence,
         transposition::Bound, worker::Worker,
     },
 };
 
+// Minimum depth for internal iterative reductions
+const IIR_DEPTH: i32 = 4;
+
+// Minimum depth, margin above beta and reduction for ProbCut
+const PROBCUT_DEPTH: i32 = 5;
+const PROBCUT_MARGIN: i32 = 200;
+const PROBCUT_REDUCTION: i32 = 4;
+
 #[derive(Clone)]
 pub struct PrincipalVariation {
     line: ArrayVec<Move, MAX_DEPTH>,
@@ -41,6 +49,10 @@ impl PrincipalVariation {
         self.score
     }
 
+    pub fn line(&self) -> &[Move] {
+        &self.line
+    }
+
     pub fn collect(&mut self, mov: Move, score: i32, other: &Self) {
         self.score = score;
 
@@ -55,7 +67,7 @@ pub fn pvs<TYPE: NodeType>(
     pv: &mut PrincipalVariation,
     mut alpha: i32,
     mut beta: i32,
-    depth: i32,
+    mut depth: i32,
 ) -> i32 {
     debug_assert!(-INF <= alpha && alpha < beta && beta <= INF);
     debug_assert!(worker.pos.height() < MAX_DEPTH);
@@ -76,11 +88,22 @@ pub fn pvs<TYPE: NodeType>(
 
     let height = worker.pos.height();
 
+    worker.update_seldepth(height);
+
     if !TYPE::ROOT {
         if worker.abort() || worker.pos.draw() {
             return DRAW;
         }
 
+        // We can repeat a position of the search with our next move, so we get at least a draw
+        if alpha < DRAW && worker.pos.upcoming_repetition() {
+            alpha = DRAW;
+
+            if alpha >= beta {
+                return alpha;
+            }
+        }
+
         alpha = alpha.max(mated_in(height));
         beta = beta.min(mate_in(height + 1));
 
@@ -112,11 +135,67 @@ pub fn pvs<TYPE: NodeType>(
         None
     };
 
-    let mut moves = MoveList::new();
-    worker.pos.generate::<All>(&mut moves);
+    // Without a move from an earlier search, the node is likely not worth the full depth,
+    // and searching it shallower gives us a move for the next time
+    if !TYPE::ROOT && tt_move.is_none() && depth >= IIR_DEPTH {
+        depth -= 1;
+    }
 
     let check = worker.pos.check();
 
+    // A good capture, which beats beta by a margin at a reduced depth,
+    // will most likely beat beta at the full depth as well
+    if !TYPE::PV && !check && depth >= PROBCUT_DEPTH && beta.abs() < MATE - PROBCUT_MARGIN {
+        let probcut_beta = beta + PROBCUT_MARGIN;
+        let threshold = probcut_beta - evaluate(&worker.pos);
+
+        let mut local_pv = PrincipalVariation::EMPTY;
+        let mut picker = MovePicker::new(tt_move.filter(|mov| mov.tactical()));
+        let mut legal = 0;
+
+        picker.set_quiet(false);
+
+        while let Some(mov) = picker.next(worker) {
+            if !worker.pos.legal(mov) || !worker.pos.see(mov, threshold) {
+                continue;
+            }
+
+            legal += 1;
+
+            worker.pos.make_move(mov);
+
+            // We verify with quiescence search first, as it is much cheaper
+            let mut score = -quiescence(worker, -probcut_beta, -probcut_beta + 1);
+
+            if score >= probcut_beta {
+                let new_depth = depth - PROBCUT_REDUCTION;
+                score = -pvs::<NonPV>(
+                    worker,
+                    &mut local_pv,
+                    -probcut_beta,
+                    -probcut_beta + 1,
+                    new_depth,
+                );
+            }
+
+            worker.pos.unmake_move(mov);
+
+            if score >= probcut_beta {
+                worker.update_nodes(legal);
+
+                #[rustfmt::skip]
+                worker.tt.insert(zobrist, Some(mov), score, depth - PROBCUT_REDUCTION + 1, Bound::Lower, height);
+
+                return score;
+            }
+        }
+
+        worker.update_nodes(legal);
+    }
+
+    let mut moves = MoveList::new();
+    worker.pos.generate::<All>(&mut moves);
+
     let mut best_score = -INF;
     let mut best_move = None;
 
@@ -133,14 +212,25 @@ pub fn pvs<TYPE: NodeType>(
 
         legal += 1;
 
+        if TYPE::ROOT {
+            worker.report_move(depth, mov, legal as usize);
+        }
+
+        let new_depth = depth - 1;
+
+        // The child probes its entry right away, unless it drops into quiescence search
+        if new_depth > 0 {
+            worker.tt.prefetch(worker.pos.key_after(mov));
+        }
+
         worker.pos.make_move(mov);
 
         if !TYPE::PV || legal > 1 {
-            score = -pvs::<NonPV>(worker, &mut local_pv, -(alpha + 1), -alpha, depth - 1);
+            score = -pvs::<NonPV>(worker, &mut local_pv, -(alpha + 1), -alpha, new_depth);
         }
 
         if TYPE::PV && (legal == 1 || score > alpha) {
-            score = -pvs::<PV>(worker, &mut local_pv, -beta, -alpha, depth - 1);
+            score = -pvs::<PV>(worker, &mut local_pv, -beta, -alpha, new_depth);
         }
 
         worker.pos.unmake_move(mov);
@@ -160,6 +250,11 @@ pub fn pvs<TYPE: NodeType>(
             pv.collect(mov, score, &local_pv);
         }
 
+        // A later root move improved our score, before the iteration is finished
+        if TYPE::ROOT && legal > 1 && score < beta && !worker.abort() {
+            worker.report_bound(depth, pv, Bound::Lower);
+        }
+
         if alpha < beta {
             continue;
         }
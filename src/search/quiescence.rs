@@ -12,6 +12,8 @@ pub fn quiescence(worker: &mut Worker, mut alpha: i32, beta: i32) -> i32 {
         worker.check_limits();
     }
 
+    worker.update_seldepth(worker.pos.height());
+
     let check = worker.pos.check();
 
     if worker.abort() || worker.pos.draw() {
@@ -1,4 +1,5 @@
 mod entry;
+mod memory;
 mod table;
 mod view;
 
@@ -1,8 +1,8 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 
-use types::Move;
+use types::{Move, PieceType};
 
-use crate::search::transposition::Bound;
+use crate::{evaluation::INF, search::transposition::Bound};
 
 const _: () = assert!(
     std::mem::size_of::<TranspositionEntry>() == std::mem::size_of::<TranspositionInternal>()
@@ -24,6 +24,43 @@ impl TranspositionInternal {
     }
 }
 
+/// Raw entries from outside, e.g. a saved table, are only valid with a known bound,
+/// a move with a defined flag, and a score within the search window
+impl TryFrom<u64> for TranspositionInternal {
+    type Error = &'static str;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        // The bound is stored in the last byte of the entry
+        if (value >> 56) as u8 > Bound::Lower as u8 {
+            return Err("bound");
+        }
+
+        // The flag takes the upper four bits of the move, which is zero for no move
+        let flag = (value >> 28) as u8 & 0b1111;
+        let promotion = flag & 0b1000 != 0;
+
+        let piece = flag & 0b0111;
+        let valid = if promotion {
+            (PieceType::Knight as u8..=PieceType::Queen as u8).contains(&piece)
+        } else {
+            // En-passant is the last of the flags without a promotion
+            flag <= 0b0101
+        };
+
+        if !valid {
+            return Err("move");
+        }
+
+        let score = i32::from((value >> 32) as u16 as i16);
+
+        if score.abs() > INF {
+            return Err("score");
+        }
+
+        Ok(Self(AtomicU64::new(value)))
+    }
+}
+
 #[repr(C)]
 pub struct TranspositionEntry {
     pub(super) key: u16,
@@ -0,0 +1,153 @@
+use std::{
+    alloc::{self, Layout},
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+};
+
+use crate::search::transposition::entry::TranspositionInternal;
+
+/// Zero-initialized entries of the table, which are backed by huge pages if possible
+pub struct Memory {
+    ptr: NonNull<TranspositionInternal>,
+    len: usize,
+}
+
+// Safety: the entries are only accessed atomically through shared references
+unsafe impl Send for Memory {}
+unsafe impl Sync for Memory {}
+
+impl Memory {
+    pub const EMPTY: Self = Self {
+        ptr: NonNull::dangling(),
+        len: 0,
+    };
+
+    pub fn new(len: usize) -> Self {
+        if len == 0 {
+            return Self::EMPTY;
+        }
+
+        let layout = Self::layout(len);
+
+        match NonNull::new(unsafe { sys::allocate(layout) }) {
+            Some(ptr) => Self {
+                ptr: ptr.cast(),
+                len,
+            },
+            None => alloc::handle_alloc_error(layout),
+        }
+    }
+
+    fn layout(len: usize) -> Layout {
+        Layout::array::<TranspositionInternal>(len).expect("size of the table must fit in memory")
+    }
+}
+
+impl Deref for Memory {
+    type Target = [TranspositionInternal];
+
+    fn deref(&self) -> &Self::Target {
+        // Safety: `ptr` points to `len` zero-initialized entries, which are valid when zeroed
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for Memory {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // Safety: see `deref`
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for Memory {
+    fn drop(&mut self) {
+        if self.len != 0 {
+            unsafe { sys::deallocate(self.ptr.as_ptr().cast(), Self::layout(self.len)) };
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::{alloc::Layout, ffi::c_void, ptr};
+
+    const PROT_READ: i32 = 0x1;
+    const PROT_WRITE: i32 = 0x2;
+    const MAP_PRIVATE: i32 = 0x2;
+    const MAP_ANONYMOUS: i32 = 0x20;
+    const MADV_HUGEPAGE: i32 = 14;
+
+    const HUGE_PAGE: usize = 2 * 1024 * 1024;
+
+    unsafe extern "C" {
+        fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: i32,
+            flags: i32,
+            fd: i32,
+            off: i64,
+        ) -> *mut c_void;
+        fn munmap(addr: *mut c_void, len: usize) -> i32;
+        fn madvise(addr: *mut c_void, len: usize, advice: i32) -> i32;
+    }
+
+    /// Anonymous mappings are zeroed, and only backed by memory once they are touched
+    pub unsafe fn allocate(layout: Layout) -> *mut u8 {
+        let len = size(layout);
+
+        unsafe {
+            // One more huge page lets us start the table on a huge page boundary
+            let ptr = mmap(
+                ptr::null_mut(),
+                len + HUGE_PAGE,
+                PROT_READ | PROT_WRITE,
+                MAP_PRIVATE | MAP_ANONYMOUS,
+                -1,
+                0,
+            );
+
+            // MAP_FAILED
+            if ptr as isize == -1 {
+                return ptr::null_mut();
+            }
+
+            // The slack around the aligned table is given back, so only the table stays mapped
+            let head = (ptr as usize).next_multiple_of(HUGE_PAGE) - ptr as usize;
+            let aligned = ptr.byte_add(head);
+
+            if head != 0 {
+                munmap(ptr, head);
+            }
+
+            munmap(aligned.byte_add(len), HUGE_PAGE - head);
+
+            // Huge pages are only a hint, so we can ignore it failing
+            madvise(aligned, len, MADV_HUGEPAGE);
+
+            aligned.cast()
+        }
+    }
+
+    pub unsafe fn deallocate(ptr: *mut u8, layout: Layout) {
+        unsafe { munmap(ptr.cast(), size(layout)) };
+    }
+
+    // Whole huge pages allow the kernel to back the end of the table with one as well
+    fn size(layout: Layout) -> usize {
+        layout.size().next_multiple_of(HUGE_PAGE)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::alloc::{self, Layout};
+
+    pub unsafe fn allocate(layout: Layout) -> *mut u8 {
+        unsafe { alloc::alloc_zeroed(layout) }
+    }
+
+    pub unsafe fn deallocate(ptr: *mut u8, layout: Layout) {
+        unsafe { alloc::dealloc(ptr, layout) };
+    }
+}
@@ -1,37 +1,253 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+    thread,
+};
+
 use crate::search::transposition::{
-    TranspositionEntry, TranspositionView, entry::TranspositionInternal,
+    TranspositionEntry, TranspositionView, entry::TranspositionInternal, memory::Memory,
 };
 
 pub struct TranspositionTable {
-    table: Vec<TranspositionInternal>,
+    table: Memory,
 }
 
 impl TranspositionTable {
     const MEGABYTE: usize = 1024 * 1024;
 
+    // The header of a saved table consists of the magic bytes, the version,
+    // and the number of entries, which are followed by the entries themselves
+    const MAGIC: &[u8; 8] = b"mort-tt\0";
+    // Has to be increased, if the layout or the indexing of the entries changes
+    const VERSION: u32 = 2;
+
     pub const fn new() -> Self {
-        Self { table: Vec::new() }
+        Self {
+            table: Memory::EMPTY,
+        }
     }
 
     pub fn view(&self) -> TranspositionView<'_> {
         TranspositionView { table: &self.table }
     }
+
     pub fn resize(&mut self, mb: usize) {
-        let size = mb * Self::MEGABYTE / TranspositionEntry::SIZE;
+        // Drop the old table first, so both don't have to fit into memory at once
+        self.table = Memory::EMPTY;
+        // Even without memory, we need a single entry to index into
+        self.table = Memory::new((mb * Self::MEGABYTE / TranspositionEntry::SIZE).max(1));
+    }
+
+    pub fn clear(&mut self) {
+        let threads = thread::available_parallelism().map_or(1, usize::from);
+        let chunk = self.table.len().div_ceil(threads).max(1);
 
-        self.table = Vec::with_capacity(size);
+        thread::scope(|s| {
+            for chunk in self.table.chunks_mut(chunk) {
+                s.spawn(|| chunk.fill_with(|| TranspositionInternal::EMPTY));
+            }
+        });
+    }
+
+    /// Write all entries to `path`, so the table can be restored with [`Self::load`]
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
 
-        // Safety: `table` capacity is equal to `size`
-        unsafe {
-            self.table.set_len(size);
+        writer.write_all(Self::MAGIC)?;
+        writer.write_all(&Self::VERSION.to_le_bytes())?;
+        writer.write_all(&(self.table.len() as u64).to_le_bytes())?;
+
+        for entry in self.table.iter() {
+            writer.write_all(&entry.load().to_le_bytes())?;
         }
 
-        self.clear();
+        writer.flush()
     }
 
-    pub fn clear(&mut self) {
-        for entry in self.table.iter_mut() {
-            *entry = TranspositionInternal::EMPTY;
+    /// Replace the table with the one saved at `path`, which also restores its size
+    pub fn load(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = File::open(path)?;
+        let size = file.metadata()?.len();
+
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0; 8];
+        let mut version = [0; 4];
+        let mut len = [0; 8];
+
+        reader.read_exact(&mut magic)?;
+        reader.read_exact(&mut version)?;
+        reader.read_exact(&mut len)?;
+
+        if &magic != Self::MAGIC {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "not a transposition table",
+            ));
+        }
+
+        if u32::from_le_bytes(version) != Self::VERSION {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("unsupported version {}", u32::from_le_bytes(version)),
+            ));
+        }
+
+        // We don't trust the header, so the entries have to be present in the file
+        let len = u64::from_le_bytes(len);
+        let header = (magic.len() + version.len() + 8) as u64;
+
+        if len > size.saturating_sub(header) / TranspositionEntry::SIZE as u64 {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("missing entries of {}", len),
+            ));
+        }
+
+        let len = len as usize;
+
+        // Reading all entries first keeps the current table, if the file is corrupted
+        let mut entries = vec![0; len * TranspositionEntry::SIZE];
+        reader.read_exact(&mut entries)?;
+
+        let entries = entries
+            .chunks_exact(TranspositionEntry::SIZE)
+            .map(|bytes| {
+                let entry = u64::from_le_bytes(bytes.try_into().unwrap());
+
+                TranspositionInternal::try_from(entry).map_err(|field| {
+                    io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("invalid {} of entry {:#018x}", field, entry),
+                    )
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        self.table = Memory::EMPTY;
+        self.table = Memory::new(len);
+
+        for (entry, loaded) in self.table.iter_mut().zip(entries) {
+            *entry = loaded;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use crate::{
+        evaluation::{INF, MATE},
+        search::transposition::{Bound, TranspositionTable},
+    };
+
+    #[test]
+    fn save_load() {
+        let path = env::temp_dir().join(format!("mort-tt-{}", std::process::id()));
+
+        let mut tt = TranspositionTable::new();
+        tt.resize(1);
+
+        for zobrist in 1..1000u64 {
+            let zobrist = zobrist.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+            tt.view().insert(zobrist, None, 7, 3, Bound::Lower, 0);
         }
+
+        tt.save(&path).unwrap();
+
+        let mut loaded = TranspositionTable::new();
+        loaded.load(&path).unwrap();
+
+        let (tt, loaded) = (tt.view(), loaded.view());
+
+        assert_eq!(tt.table.len(), loaded.table.len());
+        assert!(
+            tt.table
+                .iter()
+                .zip(loaded.table.iter())
+                .all(|(a, b)| a.load() == b.load())
+        );
+
+        // A file without the header is rejected
+        fs::write(&path, [0; 64]).unwrap();
+        assert!(TranspositionTable::new().load(&path).is_err());
+
+        let header = |len: u64| {
+            let mut bytes = b"mort-tt\0".to_vec();
+            bytes.extend(TranspositionTable::VERSION.to_le_bytes());
+            bytes.extend(len.to_le_bytes());
+            bytes
+        };
+
+        // A valid header with an unknown bound is rejected
+        let mut bytes = header(2);
+        bytes.extend(0u64.to_le_bytes());
+        bytes.extend((3u64 << 56 | 1).to_le_bytes());
+        fs::write(&path, bytes).unwrap();
+        assert!(TranspositionTable::new().load(&path).is_err());
+
+        // An undefined flag, a king promotion, or a score beyond `INF` are rejected
+        for entry in [6u64 << 28 | 1, 0b1101 << 28 | 1, (INF as u64 + 1) << 32] {
+            let mut bytes = header(1);
+            bytes.extend(entry.to_le_bytes());
+            fs::write(&path, bytes).unwrap();
+            assert!(TranspositionTable::new().load(&path).is_err());
+        }
+
+        // A promotion to a queen with a mate score is fine
+        let mut bytes = header(1);
+        bytes.extend((0b1100u64 << 28 | 1 << 16 | (MATE as u64) << 32).to_le_bytes());
+        fs::write(&path, bytes).unwrap();
+        assert!(TranspositionTable::new().load(&path).is_ok());
+
+        // The number of entries can't exceed the size of the file
+        let mut bytes = header(u64::MAX);
+        bytes.extend(0u64.to_le_bytes());
+        fs::write(&path, bytes).unwrap();
+        assert!(TranspositionTable::new().load(&path).is_err());
+
+        // A failed load keeps the current table
+        let mut loaded = TranspositionTable::new();
+        loaded.resize(1);
+        assert!(loaded.load(&path).is_err());
+        assert_eq!(loaded.view().table.len(), tt.table.len());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn non_power_of_two() {
+        let mut tt = TranspositionTable::new();
+        tt.resize(3);
+
+        let keys: Vec<u64> = (1..100u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect();
+
+        for &zobrist in keys.iter() {
+            tt.view().insert(zobrist, None, 7, 3, Bound::Lower, 0);
+        }
+
+        for &zobrist in keys.iter() {
+            assert!(tt.view().probe(zobrist, 0).is_some());
+        }
+
+        tt.clear();
+        assert!(tt.view().probe(keys[0], 0).is_none());
+
+        // A table without any memory still has a single entry
+        tt.resize(0);
+        tt.view().insert(keys[0], None, 7, 3, Bound::Lower, 0);
+        assert!(tt.view().probe(keys[0], 0).is_some());
     }
 }
@@ -60,13 +60,37 @@ impl TranspositionView<'_> {
         Some(entry)
     }
 
+    /// Load the entry of `zobrist` into the cache, so a later probe doesn't have to wait for it
+    pub fn prefetch(&self, zobrist: Key) {
+        #[cfg(target_arch = "x86_64")]
+        unsafe {
+            use std::arch::x86_64::{_MM_HINT_T0, _mm_prefetch};
+
+            let entry = &self.table[self.index(zobrist)];
+            _mm_prefetch::<_MM_HINT_T0>((entry as *const TranspositionInternal).cast());
+        }
+
+        #[cfg(not(target_arch = "x86_64"))]
+        let _ = zobrist;
+    }
+
+    /// Occupied entries per mille, which we estimate from the first entries
+    pub fn hashfull(&self) -> usize {
+        const SAMPLE: usize = 1000;
+
+        let sample = &self.table[..SAMPLE.min(self.table.len())];
+        let occupied = sample.iter().filter(|entry| entry.load() != 0).count();
+
+        occupied * 1000 / sample.len().max(1)
+    }
+
+    // Maps the key uniformly onto the table, which also works for sizes other than powers of two
     fn index(&self, zobrist: Key) -> usize {
-        (zobrist as usize) & (self.table.len() - 1)
+        ((u128::from(zobrist) * self.table.len() as u128) >> 64) as usize
     }
 
+    // The index is taken from the upper bits, so the lower bits verify the entry
     fn checksum(zobrist: Key) -> u16 {
-        const KEY_SHIFT: u16 = 48;
-
-        (zobrist >> KEY_SHIFT) as u16
+        zobrist as u16
     }
 }
@@ -1,5 +1,4 @@
 use std::{
-    fmt::Display,
     sync::atomic::{AtomicBool, Ordering},
     time::Instant,
 };
@@ -9,34 +8,33 @@ use types::Move;
 use crate::{
     chess::Position,
     search::{
-        SearchLimit, history::ButterflyHistory, pv::PrincipalVariation,
-        transposition::TranspositionView,
+        SearchLimit,
+        history::ButterflyHistory,
+        observer::{Iteration, SearchObserver},
+        pv::PrincipalVariation,
+        transposition::{Bound, TranspositionView},
     },
 };
 
 struct Info {
     start: Instant,
     nodes: u64,
+    seldepth: usize,
+    // Depth of the last finished iteration
+    depth: i32,
+    // Number of limit checks, so we only read the clock periodically
+    checks: u64,
     pv: PrincipalVariation,
 }
 
-impl Display for Info {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "score cp {} nodes {} pv {}",
-            self.pv.score(),
-            self.nodes,
-            self.pv
-        )
-    }
-}
-
 impl Info {
     fn new() -> Self {
         Self {
             start: Instant::now(),
             nodes: 0,
+            seldepth: 0,
+            depth: 0,
+            checks: 0,
             pv: PrincipalVariation::EMPTY,
         }
     }
@@ -53,15 +51,19 @@ pub struct Worker<'a> {
     limits: SearchLimit,
     info: Info,
     abort: &'a AtomicBool,
+    observer: &'a dyn SearchObserver,
     main: bool,
 }
 
 impl<'a> Worker<'a> {
+    const CHECK_INTERVAL: u64 = 1024;
+
     pub fn new(
         pos: Position,
         tt: TranspositionView<'a>,
         limits: SearchLimit,
         abort: &'a AtomicBool,
+        observer: &'a dyn SearchObserver,
         main: bool,
     ) -> Self {
         Self {
@@ -71,16 +73,25 @@ impl<'a> Worker<'a> {
             limits,
             info: Info::new(),
             abort,
+            observer,
             main,
         }
     }
 
+    /// We always finish the first iteration, so we have a legal move to play
     pub fn abort(&self) -> bool {
-        self.abort.load(Ordering::Relaxed)
+        self.info.depth > 0 && self.abort.load(Ordering::Relaxed)
     }
 
-    pub fn check_limits(&self) {
-        if self.limits.check(self.info.elapsed(), self.info.nodes) {
+    pub fn check_limits(&mut self) {
+        self.info.checks += 1;
+
+        // Reading the clock is expensive compared to searching a node
+        let exceeded = self.limits.exceeds_nodes(self.info.nodes)
+            || (self.info.checks.is_multiple_of(Self::CHECK_INTERVAL)
+                && self.limits.exceeds_time(self.info.elapsed()));
+
+        if exceeded {
             self.abort.store(true, Ordering::Relaxed);
         }
     }
@@ -93,12 +104,55 @@ impl<'a> Worker<'a> {
         self.info.nodes += nodes;
     }
 
-    pub fn update_pv(&mut self, pv: &PrincipalVariation) {
+    pub fn update_seldepth(&mut self, height: usize) {
+        self.info.seldepth = self.info.seldepth.max(height);
+    }
+
+    /// Each iteration reports its own selective depth
+    pub fn reset_seldepth(&mut self) {
+        self.info.seldepth = 0;
+    }
+
+    pub fn update_pv(&mut self, depth: i32, pv: &PrincipalVariation) {
+        self.info.depth = depth;
         self.info.pv = pv.clone();
     }
 
     pub fn report(&self, depth: i32) {
-        println!("info depth {} {}", depth, self.info);
+        if self.main {
+            self.observer
+                .iteration(&self.iteration(depth, &self.info.pv));
+        }
+    }
+
+    pub fn report_move(&self, depth: i32, mov: Move, number: usize) {
+        if self.main {
+            self.observer.current_move(depth, mov, number);
+        }
+    }
+
+    pub fn report_bound(&self, depth: i32, pv: &PrincipalVariation, bound: Bound) {
+        if self.main {
+            self.observer.bound(&self.iteration(depth, pv), bound);
+        }
+    }
+
+    pub fn report_best_move(&self, mov: Option<Move>) {
+        if self.main {
+            self.observer.best_move(mov);
+        }
+    }
+
+    fn iteration(&self, depth: i32, pv: &PrincipalVariation) -> Iteration {
+        Iteration {
+            depth,
+            seldepth: self.info.seldepth,
+            score: pv.score(),
+            nodes: self.info.nodes,
+            time: self.info.elapsed(),
+            hashfull: self.tt.hashfull(),
+            pv: pv.line().to_vec(),
+        }
     }
 
     pub fn result(&self) -> (i32, Option<Move>) {
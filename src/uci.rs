@@ -1,57 +1,106 @@
 use std::{
     collections::VecDeque,
-    io, process,
+    io,
     slice::Iter,
-    sync::atomic::{AtomicBool, Ordering},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    },
     thread,
+    time::{Duration, Instant},
 };
 
-use types::Color;
+use types::{Color, Move};
 
 use crate::{
+    START_POS,
     chess::{All, MoveList, Position},
-    error::Error,
-    evaluation::evaluate,
-    ok_or,
-    search::{SearchLimit, TranspositionTable, go},
-    syntax_error, unwrap_or,
-    util::{bench, perft},
+    error::{Error, UciError},
+    evaluation::{INF, MATE, evaluate, material, normalize, trace, wdl},
+    search::{Bound, Iteration, SearchLimit, SearchObserver, TranspositionTable, go},
+    unwrap_or,
+    util::{bench, calibrate, perft, perftsuite},
 };
 
-const START_POS: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
-
 mod default {
     pub const TT_SIZE: usize = 16;
     pub const OVERHEAD: u16 = 10;
+    pub const REPORT_DELAY: u128 = 3000;
 }
 
-fn read() -> Option<String> {
-    let mut input = String::new();
+// How often we look for new commands, while waiting for the search to finish
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
 
-    let bytes_read = match io::stdin().read_line(&mut input) {
-        Ok(bytes_read) => bytes_read,
-        Err(_) => return None,
-    };
+/// Values of the UCI options, which aren't stored elsewhere
+struct Options {
+    overhead: u16,
+    show_wdl: bool,
+}
 
-    // We received EOF
-    if bytes_read == 0 {
-        process::exit(0);
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            overhead: default::OVERHEAD,
+            show_wdl: false,
+        }
     }
+}
+
+/// Start and moves of the last `position` command, so the next one only has to play
+/// the moves it adds
+#[derive(Default)]
+struct Game {
+    fen: String,
+    moves: Vec<String>,
+}
+
+/// Forward each line of stdin as a command, until we receive EOF
+fn read(sender: Sender<String>) {
+    for line in io::stdin().lines() {
+        let Ok(line) = line else {
+            break;
+        };
 
-    Some(input)
+        if sender.send(line).is_err() {
+            break;
+        }
+    }
 }
 
-pub fn run(mut buffer: VecDeque<String>) {
+/// Handle the commands in `buffer` first, and then the ones read from stdin
+pub fn run(buffer: VecDeque<String>) {
+    let (sender, receiver) = mpsc::channel();
+
+    for command in buffer {
+        sender.send(command).unwrap();
+    }
+
+    thread::spawn(move || read(sender));
+
+    execute(receiver);
+}
+
+/// Handle all commands received from `receiver`, until we receive `quit`
+/// or all senders are dropped
+pub fn execute(receiver: Receiver<String>) {
     let mut pos = Position::from_fen(START_POS).unwrap();
     let mut tt = TranspositionTable::new();
-    let mut overhead = default::OVERHEAD;
+    let mut options = Options::default();
+    let mut game = Game::default();
+
+    // Commands received while searching, which are handled afterwards
+    let mut pending = VecDeque::new();
 
     tt.resize(default::TT_SIZE);
 
     loop {
-        let input = match buffer.pop_front().or_else(read) {
+        let input = match pending.pop_front() {
             Some(input) => input,
-            None => continue,
+            None => match receiver.recv() {
+                Ok(input) => input,
+                Err(_) => return,
+            },
         };
 
         let commands: Vec<_> = input.split_ascii_whitespace().collect();
@@ -61,24 +110,143 @@ pub fn run(mut buffer: VecDeque<String>) {
         };
 
         match command {
-            "quit" => process::exit(0),
+            "quit" => return,
             "uci" => identify(),
-            "setoption" => unwrap_or!(handle_option(commands, &mut tt, &mut overhead)),
-            "position" => unwrap_or!(handle_position(&mut pos, commands)),
+            "setoption" => unwrap_or!(handle_option(commands, &mut tt, &mut options)),
+            "position" => unwrap_or!(handle_position(&mut pos, &mut game, commands)),
             "ucinewgame" => {
                 pos = Position::from_fen(START_POS).unwrap();
+                game = Game::default();
                 tt.clear();
             }
             "isready" => println!("readyok"),
-            "go" => unwrap_or!(handle_go(&pos, &tt, overhead, commands, &mut buffer)),
-            "bench" => bench(&tt, commands),
+            "go" => unwrap_or!(handle_go(
+                &pos,
+                &tt,
+                &options,
+                commands,
+                &receiver,
+                &mut pending
+            )),
+            "bench" => bench(&tt, commands, &UciPrinter::new()),
+            "savehash" => unwrap_or!(handle_hash_file(&mut tt, commands)),
+            "loadhash" => unwrap_or!(handle_hash_file(&mut tt, commands)),
+            "perftsuite" => unwrap_or!(perftsuite(commands)),
+            "calibrate" => calibrate(commands),
             "d" => println!("{}", pos),
-            "eval" => println!("score cp {}", evaluate(&pos)),
+            "flip" => {
+                pos = pos.flip();
+                game = Game::default();
+            }
+            "eval" => println!("score cp {}", normalize(evaluate(&pos))),
+            "trace" => unwrap_or!(trace(&pos).map(|trace| println!("{}", trace))),
             _ => eprintln!("Unknown command: {}", command),
         };
     }
 }
 
+/// Prints the progress of the search as UCI info lines
+pub struct UciPrinter {
+    start: Instant,
+    // Material of the root position, if the scores are also printed as WDL
+    material: Option<i32>,
+}
+
+impl UciPrinter {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            material: None,
+        }
+    }
+
+    /// Append the expected win, draw and loss chance of `pos` to each score
+    pub fn with_wdl(mut self, pos: &Position) -> Self {
+        self.material = Some(material(pos.layout()));
+        self
+    }
+
+    fn info(&self, iteration: &Iteration, bound: Option<Bound>) -> String {
+        let mut info = format!(
+            "info depth {} seldepth {} score {}",
+            iteration.depth,
+            iteration.seldepth,
+            score(iteration.score)
+        );
+
+        match bound {
+            Some(Bound::Lower) => info.push_str(" lowerbound"),
+            Some(Bound::Upper) => info.push_str(" upperbound"),
+            _ => {}
+        }
+
+        if let Some(material) = self.material {
+            let (win, draw, loss) = wdl(iteration.score, material);
+            info.push_str(&format!(" wdl {} {} {}", win, draw, loss));
+        }
+
+        let nps = iteration.nodes as u128 * 1000 / iteration.time.max(1);
+
+        info.push_str(&format!(
+            " nodes {} nps {} hashfull {} time {} pv",
+            iteration.nodes, nps, iteration.hashfull, iteration.time
+        ));
+
+        for mov in iteration.pv.iter() {
+            info.push_str(&format!(" {}", mov));
+        }
+
+        info
+    }
+}
+
+impl Default for UciPrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchObserver for UciPrinter {
+    fn iteration(&self, iteration: &Iteration) {
+        println!("{}", self.info(iteration, None));
+    }
+
+    fn current_move(&self, depth: i32, mov: Move, number: usize) {
+        // Short searches would flood the output with intermediate results
+        if self.start.elapsed().as_millis() >= default::REPORT_DELAY {
+            println!(
+                "info depth {} currmove {} currmovenumber {}",
+                depth, mov, number
+            );
+        }
+    }
+
+    fn bound(&self, iteration: &Iteration, bound: Bound) {
+        if iteration.time >= default::REPORT_DELAY {
+            println!("{}", self.info(iteration, Some(bound)));
+        }
+    }
+
+    fn best_move(&self, mov: Option<Move>) {
+        match mov {
+            Some(mov) => println!("bestmove {}", mov),
+            None => println!("bestmove 0000"),
+        }
+    }
+}
+
+/// Convert `score` to either centipawns, or moves until mate, where negative moves mean we are mated
+fn score(score: i32) -> String {
+    if score.abs() <= MATE {
+        return format!("cp {}", normalize(score));
+    }
+
+    let ply = INF - score.abs();
+    let moves = (ply + 1) / 2;
+
+    format!("mate {}", if score > 0 { moves } else { -moves })
+}
+
 fn identify() {
     println!(concat!(
         "id name mort-",
@@ -86,12 +254,14 @@ fn identify() {
         '\n',
         "id author jeimel",
         '\n',
-        "option name Hash type spin default 16 min 1 max 1024",
+        "option name Hash type spin default 16 min 1 max 65536",
         '\n',
         "option name Clear Hash type button",
         '\n',
         "option name Overhead type spin default 10 min 0 max 5000",
         '\n',
+        "option name UCI_ShowWDL type check default false",
+        '\n',
         "uciok",
     ));
 }
@@ -99,25 +269,41 @@ fn identify() {
 fn handle_option(
     commands: Vec<&str>,
     tt: &mut TranspositionTable,
-    overhead: &mut u16,
+    options: &mut Options,
 ) -> Result<(), Error> {
     match commands[1..] {
-        ["name", "Hash", "value", x] => tt.resize(ok_or!(x.parse().ok(), "integer", x)),
+        ["name", "Hash", "value", x] => tt.resize(parse("Hash", x)?),
         ["name", "Clear", "Hash"] => tt.clear(),
-        ["name", "Overhead", "value", x] => *overhead = ok_or!(x.parse().ok(), "integer", x),
+        ["name", "Overhead", "value", x] => options.overhead = parse("Overhead", x)?,
+        ["name", "UCI_ShowWDL", "value", x] => options.show_wdl = parse("UCI_ShowWDL", x)?,
         #[rustfmt::skip]
-        _ => return Err(Error::Uci(syntax_error!("name <id> value <x>", commands[1..].join(" ")))),
+        _ => return Err(UciError::syntax("name <id> value <x>", commands[1..].join(" ")).into()),
     };
 
     Ok(())
 }
 
-fn handle_position(pos: &mut Position, commands: Vec<&str>) -> Result<(), Error> {
+/// Save the transposition table to, or load it from the given file
+fn handle_hash_file(tt: &mut TranspositionTable, commands: Vec<&str>) -> Result<(), Error> {
+    let path = match commands[1..] {
+        [path] => path,
+        _ => return Err(UciError::syntax("<file>", commands[1..].join(" ")).into()),
+    };
+
+    let result = match commands[0] {
+        "savehash" => tt.save(path),
+        _ => tt.load(path),
+    };
+
+    result.map_err(|err| UciError::Io(path.to_string(), err).into())
+}
+
+fn handle_position(pos: &mut Position, game: &mut Game, commands: Vec<&str>) -> Result<(), Error> {
     let mut commands = commands.iter().peekable().skip(1);
 
     let mut fen = match *commands.next().unwrap() {
         "fen" | "startpos" => String::new(),
-        command => return Err(Error::Uci(syntax_error!("[fen|startpos]", command))),
+        command => return Err(UciError::syntax("[fen|startpos]", command).into()),
     };
 
     // We iterate until we are either empty, or we found the "moves" token, which we will consume
@@ -133,20 +319,31 @@ fn handle_position(pos: &mut Position, commands: Vec<&str>) -> Result<(), Error>
         fen.as_str()
     };
 
-    *pos = match Position::from_fen(fen) {
-        Ok(pos) => pos,
-        Err(err) => return Err(err),
-    };
+    // We already skipped the "moves" token earlier, so only the moves are left
+    let moves: Vec<&str> = commands.copied().collect();
+
+    // If the game just continued, we keep our position and its history
+    let continued = game.fen == fen
+        && game.moves.len() <= moves.len()
+        && game.moves.iter().zip(moves.iter()).all(|(a, b)| a == b);
+
+    if !continued {
+        *pos = Position::from_fen(fen)?;
+
+        game.fen = fen.to_string();
+        game.moves.clear();
+    }
 
-    // We already skipped the "moves" token earlier, so we can directly play the moves if any
-    while let Some(str) = commands.next() {
+    for str in moves[game.moves.len()..].iter() {
         let mut moves = MoveList::new();
-        pos.generate::<All>(&mut moves);
+        pos.generate_legal::<All>(&mut moves);
 
         match moves.iter().find(|mov| &format!("{}", mov) == *str) {
             Some(mov) => pos.make_move(mov),
-            None => return Err(Error::Uci(syntax_error!("valid move", str))),
+            None => return Err(UciError::IllegalMove(str.to_string()).into()),
         };
+
+        game.moves.push(str.to_string());
     }
 
     Ok(())
@@ -155,32 +352,42 @@ fn handle_position(pos: &mut Position, commands: Vec<&str>) -> Result<(), Error>
 fn handle_go(
     pos: &Position,
     tt: &TranspositionTable,
-    overhead: u16,
+    options: &Options,
     commands: Vec<&str>,
-    buffer: &mut VecDeque<String>,
+    receiver: &Receiver<String>,
+    pending: &mut VecDeque<String>,
 ) -> Result<(), Error> {
     let abort = AtomicBool::new(false);
 
-    let limits = handle_limits(&mut commands.iter(), pos.stm(), overhead)?;
+    let limits = handle_limits(&mut commands.iter(), pos.stm(), options.overhead)?;
 
     if limits.perft != 0 {
-        perft::<true>(&mut pos.clone(), limits.perft);
+        let threads = thread::available_parallelism().map_or(1, usize::from);
+        println!("{}", perft(pos, limits.perft, threads));
 
         return Ok(());
     }
 
     thread::scope(|s| {
-        s.spawn(|| {
-            let (_, mov) = go(&pos, &limits, &tt, &abort);
-
-            match mov {
-                Some(mov) => println!("bestmove {}", mov),
-                _ => eprintln!("Internal error: No move found"),
+        let search = s.spawn(|| {
+            let printer = match options.show_wdl {
+                true => UciPrinter::new().with_wdl(pos),
+                false => UciPrinter::new(),
             };
+
+            go(pos, &limits, tt, &abort, &printer);
         });
 
-        if let Some(arg) = handle_search_input(&abort) {
-            buffer.push_back(arg);
+        while !search.is_finished() {
+            match receiver.recv_timeout(POLL_INTERVAL) {
+                Ok(input) => handle_search_input(input, &abort, pending),
+                Err(RecvTimeoutError::Timeout) => {}
+                // Nobody can stop us anymore, so we stop right away instead of searching forever
+                Err(RecvTimeoutError::Disconnected) => {
+                    abort.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
         }
 
         Ok(())
@@ -203,13 +410,10 @@ fn handle_limits(
         ($key:expr, $commands:expr) => {{
             let value = match $commands.next() {
                 Some(value) => value,
-                None => return Err(Error::Uci(format!("Missing value for {}", $key))),
+                None => return Err(UciError::MissingValue($key.to_string()).into()),
             };
 
-            match value.parse() {
-                Ok(value) => value,
-                Err(_) => return Err(Error::Uci(format!("Invalid value for {}", $key))),
-            }
+            parse($key, value)?
         }};
     }
 
@@ -241,29 +445,111 @@ fn handle_limits(
     Ok(limits)
 }
 
-fn handle_search_input(abort: &AtomicBool) -> Option<String> {
-    loop {
-        let input = match read() {
-            Some(input) => input,
-            None => continue,
-        };
+fn parse<T: FromStr>(key: &str, value: &str) -> Result<T, UciError> {
+    value.parse().map_err(|_| UciError::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+    })
+}
 
-        let command = match input.split_ascii_whitespace().next() {
-            Some(command) => command,
-            None => continue,
+/// Commands, which can't wait for the search to finish, are handled right away
+fn handle_search_input(input: String, abort: &AtomicBool, pending: &mut VecDeque<String>) {
+    match input.split_ascii_whitespace().next() {
+        Some("isready") => println!("readyok"),
+        Some("stop") => abort.store(true, Ordering::Relaxed),
+        // We still print our best move, but skip all other pending commands
+        Some("quit") => {
+            abort.store(true, Ordering::Relaxed);
+            pending.push_front(input);
+        }
+        Some(_) => pending.push_back(input),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, thread};
+
+    use crate::{
+        START_POS,
+        chess::Position,
+        uci::{Game, execute, handle_position},
+    };
+
+    #[test]
+    fn position() {
+        let position = |pos: &mut Position, game: &mut Game, command: &str| {
+            let commands = command.split_ascii_whitespace().collect();
+            handle_position(pos, game, commands).unwrap();
         };
 
-        match command {
-            "quit" => process::exit(0),
-            "isready" => println!("readyok"),
-            "stop" => abort.store(true, Ordering::Relaxed),
-            _ => return Some(input),
-        }
+        let mut pos = Position::from_fen(START_POS).unwrap();
+        let mut game = Game::default();
+
+        position(
+            &mut pos,
+            &mut game,
+            "position startpos moves g1f3 g8f6 f3g1",
+        );
+        position(
+            &mut pos,
+            &mut game,
+            "position startpos moves g1f3 g8f6 f3g1 f6g8",
+        );
+
+        // Only the new move was played, so we still know the earlier positions
+        assert_eq!(game.moves.len(), 4);
+        assert!(pos.repetition());
+
+        let mut expected = Position::from_fen(START_POS).unwrap();
+        position(
+            &mut expected,
+            &mut Game::default(),
+            "position startpos moves g1f3 g8f6 f3g1 f6g8",
+        );
+
+        assert_eq!(pos.fen().unwrap(), expected.fen().unwrap());
+        assert_eq!(pos.zobrist(), expected.zobrist());
+
+        // Another game starts from scratch
+        position(&mut pos, &mut game, "position startpos moves d2d4");
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/3P4/8/PPP1PPPP/RNBQKBNR b KQkq d3 0 1",
+            pos.fen().unwrap()
+        );
+    }
 
-        if abort.load(Ordering::Relaxed) {
-            break;
+    #[test]
+    fn channel() {
+        let (sender, receiver) = mpsc::channel();
+
+        let engine = thread::spawn(move || execute(receiver));
+
+        for command in [
+            "position startpos moves e2e4",
+            "go infinite",
+            "isready",
+            "stop",
+        ] {
+            sender.send(command.to_string()).unwrap();
         }
+
+        // The search is stopped, and the engine returns once we drop the channel
+        drop(sender);
+        engine.join().unwrap();
     }
 
-    None
+    #[test]
+    fn disconnect() {
+        let (sender, receiver) = mpsc::channel();
+
+        let engine = thread::spawn(move || execute(receiver));
+
+        sender.send("go infinite".to_string()).unwrap();
+
+        // Without any input left, an infinite search has to stop on its own
+        drop(sender);
+        engine.join().unwrap();
+    }
 }
@@ -1,7 +1,11 @@
 mod bench;
+mod calibrate;
 mod perft;
+mod perftsuite;
 mod rng;
 
 pub use bench::bench;
-pub use perft::perft;
+pub use calibrate::calibrate;
+pub use perft::{Divide, perft};
+pub use perftsuite::perftsuite;
 pub use rng::XorShiftState;
@@ -3,12 +3,12 @@ use std::sync::atomic::AtomicBool;
 use crate::{
     FEN,
     chess::Position,
-    search::{SearchLimit, TranspositionTable, go},
+    search::{SearchLimit, SearchObserver, TranspositionTable, go},
 };
 
 const DEPTH: u16 = 9;
 
-pub fn bench(tt: &TranspositionTable, args: Vec<&str>) {
+pub fn bench(tt: &TranspositionTable, args: Vec<&str>, observer: &dyn SearchObserver) {
     let depth = args.get(1).and_then(|v| v.parse().ok()).unwrap_or(DEPTH);
     let abort = AtomicBool::new(false);
 
@@ -20,6 +20,6 @@ pub fn bench(tt: &TranspositionTable, args: Vec<&str>) {
         let mut limits = SearchLimit::MAX;
         limits.depth = depth;
 
-        let _ = go(&pos, &limits, tt, &abort);
+        let _ = go(&pos, &limits, tt, &abort, observer);
     }
 }
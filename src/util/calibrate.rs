@@ -0,0 +1,285 @@
+use std::sync::atomic::AtomicBool;
+
+use crate::{
+    START_POS,
+    chess::{All, MoveList, Position},
+    evaluation::{MATE, NORMALIZE_TO_PAWN, material},
+    search::{SearchLimit, Silent, TranspositionTable, go},
+    util::XorShiftState,
+};
+
+const GAMES: usize = 100;
+const NODES: u64 = 5000;
+
+// Random moves at the start of each game, so the games differ
+const RANDOM_PLIES: usize = 8;
+// Games without a result after this many plies are counted as draws
+const MAX_PLIES: usize = 400;
+// Material range of the WDL model. Positions outside aren't sampled, since counting them at the
+// closest bound would give the polynomials a heavy outlier, which the cubic bends towards
+const MATERIAL: std::ops::RangeInclusive<i32> = 17..=78;
+// Fewer samples of a material don't give a meaningful fit
+const MIN_SAMPLES: usize = 100;
+
+/// Fit the WDL model from self-play games, which gives the polynomials of the score of an even
+/// win chance and the spread in the material. Their value at a material of 58, which is
+/// typical for move 32, is the one of [`NORMALIZE_TO_PAWN`]
+pub fn calibrate(args: Vec<&str>) {
+    let games = args.get(1).and_then(|v| v.parse().ok()).unwrap_or(GAMES);
+    let nodes = args.get(2).and_then(|v| v.parse().ok()).unwrap_or(NODES);
+
+    let mut tt = TranspositionTable::new();
+    tt.resize(16);
+
+    let mut samples = Vec::new();
+
+    for game in 0..games {
+        tt.clear();
+
+        let result = play(&tt, game as u64 + 1, nodes, &mut samples);
+        println!("Game: {}/{} ({})", game + 1, games, result);
+    }
+
+    println!("\nSamples: {}", samples.len());
+
+    // We fit the model for each material on its own first
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+
+    for material in MATERIAL {
+        let samples: Vec<_> = samples
+            .iter()
+            .filter(|sample| sample.2 == material)
+            .map(|&(score, outcome, _)| (score, outcome))
+            .collect();
+
+        if samples.len() < MIN_SAMPLES {
+            continue;
+        }
+
+        let m = f64::from(material) / 58.0;
+        let weight = samples.len() as f64;
+        let (fitted_a, fitted_b) = fit(&samples);
+
+        a.push((m, f64::from(fitted_a), weight));
+        b.push((m, f64::from(fitted_b), weight));
+    }
+
+    if a.len() < 4 {
+        println!("Not enough samples for the polynomials");
+        return;
+    }
+
+    let (a, b) = (polynomial(&a), polynomial(&b));
+    let format = |c: [f64; 4]| format!("[{:.3}, {:.3}, {:.3}, {:.3}]", c[0], c[1], c[2], c[3]);
+
+    println!("const A: [f64; 4] = {};", format(a));
+    println!("const B: [f64; 4] = {};", format(b));
+    println!(
+        "NORMALIZE_TO_PAWN is {}, and should be {}",
+        NORMALIZE_TO_PAWN,
+        a.iter().sum::<f64>().round()
+    );
+}
+
+/// Play a single game, and add the score, outcome and material of the side to move for all
+/// searched positions to `samples`
+#[rustfmt::skip]
+fn play(tt: &TranspositionTable, seed: u64, nodes: u64, samples: &mut Vec<(i32, f64, i32)>) -> f64 {
+    let mut pos = Position::from_fen(START_POS).unwrap();
+    let mut rng = XorShiftState::new(seed);
+
+    let mut limits = SearchLimit::MAX;
+    limits.nodes = nodes;
+
+    // Scores of the side to move and the material at each ply
+    let mut scores = Vec::new();
+
+    // Result for white
+    let result = loop {
+        let mut moves = MoveList::new();
+        pos.generate_legal::<All>(&mut moves);
+
+        if pos.is_checkmate() {
+            break if scores.len() % 2 == 0 { 0.0 } else { 1.0 };
+        }
+
+        if moves.is_empty() || pos.draw() || scores.len() >= MAX_PLIES {
+            break 0.5;
+        }
+
+        let mov = if scores.len() < RANDOM_PLIES {
+            let value;
+            (rng.state, value) = rng.next();
+
+            scores.push(None);
+            moves.iter().nth(value as usize % moves.len()).unwrap()
+        } else {
+            // The node limit sets the flag, so each search needs its own
+            let abort = AtomicBool::new(false);
+            let (score, mov) = go(&pos, &limits, tt, &abort, &Silent);
+
+            let material = material(pos.layout());
+            scores.push(MATERIAL.contains(&material).then_some((score, material)));
+            mov.unwrap()
+        };
+
+        // Only a threefold repetition ends the game
+        pos.make_move(mov);
+        pos.reset_height();
+    };
+
+    for (ply, score) in scores.into_iter().enumerate() {
+        let Some((score, material)) = score else {
+            continue;
+        };
+
+        if score.abs() > MATE {
+            continue;
+        }
+
+        let outcome = if ply % 2 == 0 { result } else { 1.0 - result };
+        samples.push((score, outcome, material));
+    }
+
+    result
+}
+
+/// Maximum likelihood fit of the logistic model, where the side to move wins with the chance
+/// `1 / (1 + exp((a - score) / b))`, and loses with the chance of the negated score
+fn fit(samples: &[(i32, f64)]) -> (i32, i32) {
+    let likelihood = |a: i32, b: i32| {
+        let rate = |score: i32| 1.0 / (1.0 + (f64::from(a - score) / f64::from(b)).exp());
+
+        samples
+            .iter()
+            .map(|&(score, outcome)| {
+                let (win, loss) = (rate(score), rate(-score));
+
+                let p = match outcome {
+                    1.0 => win,
+                    0.0 => loss,
+                    _ => 1.0 - win - loss,
+                };
+
+                p.max(f64::MIN_POSITIVE).ln()
+            })
+            .sum::<f64>()
+    };
+
+    let mut best = (NORMALIZE_TO_PAWN, 50);
+
+    // Refine the grid around the best parameters so far
+    for step in [16, 4, 1] {
+        let (a, b) = best;
+        let mut best_likelihood = f64::NEG_INFINITY;
+
+        for a in (a - 16 * step..=a + 16 * step).step_by(step as usize) {
+            for b in (b - 16 * step..=b + 16 * step).step_by(step as usize) {
+                if a < 1 || b < 1 {
+                    continue;
+                }
+
+                let likelihood = likelihood(a, b);
+
+                if likelihood > best_likelihood {
+                    (best, best_likelihood) = ((a, b), likelihood);
+                }
+            }
+        }
+    }
+
+    best
+}
+
+/// Weighted least squares fit of a cubic polynomial to the points `(x, y, weight)`,
+/// with the coefficients starting at the highest power
+fn polynomial(points: &[(f64, f64, f64)]) -> [f64; 4] {
+    // The normal equations of the fit, which are augmented by the right hand side
+    let mut matrix = [[0.0; 5]; 4];
+
+    for &(x, y, weight) in points {
+        let powers = [x * x * x, x * x, x, 1.0];
+
+        for i in 0..4 {
+            for j in 0..4 {
+                matrix[i][j] += weight * powers[i] * powers[j];
+            }
+
+            matrix[i][4] += weight * powers[i] * y;
+        }
+    }
+
+    // Gaussian elimination with partial pivoting
+    for i in 0..4 {
+        let pivot = (i..4)
+            .max_by(|&a, &b| matrix[a][i].abs().total_cmp(&matrix[b][i].abs()))
+            .unwrap();
+        matrix.swap(i, pivot);
+
+        let row = matrix[i];
+
+        for other in matrix[i + 1..].iter_mut() {
+            let factor = other[i] / row[i];
+
+            for (value, pivot) in other[i..].iter_mut().zip(&row[i..]) {
+                *value -= factor * pivot;
+            }
+        }
+    }
+
+    let mut coefficients = [0.0; 4];
+
+    for i in (0..4).rev() {
+        let sum: f64 = (i + 1..4).map(|j| matrix[i][j] * coefficients[j]).sum();
+        coefficients[i] = (matrix[i][4] - sum) / matrix[i][i];
+    }
+
+    coefficients
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::calibrate::{fit, polynomial};
+
+    #[test]
+    fn fit_polynomial() {
+        let expected = [-2.0, 15.0, -35.0, 50.0];
+
+        let points: Vec<_> = (17..=78)
+            .map(|material| {
+                let x = f64::from(material) / 58.0;
+                let y = ((expected[0] * x + expected[1]) * x + expected[2]) * x + expected[3];
+
+                (x, y, f64::from(material))
+            })
+            .collect();
+
+        for (fitted, expected) in polynomial(&points).into_iter().zip(expected) {
+            assert!((fitted - expected).abs() < 1e-6, "{}", fitted);
+        }
+    }
+
+    #[test]
+    fn fit_model() {
+        let (a, b) = (120.0, 60.0);
+        let rate = |score: f64| 1.0 / (1.0 + ((a - score) / b).exp());
+
+        let mut samples = Vec::new();
+
+        // Outcomes are added in the proportions of the model
+        for score in (-400..=400).step_by(20) {
+            let (win, loss) = (rate(f64::from(score)), rate(-f64::from(score)));
+
+            for (outcome, p) in [(1.0, win), (0.0, loss), (0.5, 1.0 - win - loss)] {
+                for _ in 0..(p * 1000.0).round() as usize {
+                    samples.push((score, outcome));
+                }
+            }
+        }
+
+        let (fitted_a, fitted_b) = fit(&samples);
+
+        assert!(fitted_a.abs_diff(120) <= 2, "{}", fitted_a);
+        assert!(fitted_b.abs_diff(60) <= 2, "{}", fitted_b);
+    }
+}
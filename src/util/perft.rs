@@ -1,43 +1,171 @@
-use crate::chess::{All, MoveList, Position};
+use std::{
+    fmt::Display,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    thread,
+};
 
-pub fn perft<const ROOT: bool>(pos: &mut Position, depth: u16) -> usize {
+use types::Move;
+
+use crate::chess::{All, Key, MoveList, Position};
+
+const HASH_SIZE: usize = 64;
+
+/// Leaf node counts of each root move
+pub struct Divide {
+    pub moves: Vec<(Move, u64)>,
+    pub nodes: u64,
+}
+
+impl Display for Divide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (mov, nodes) in self.moves.iter() {
+            writeln!(f, "{}: {}", mov, nodes)?;
+        }
+
+        write!(f, "\nNodes searched: {}", self.nodes)
+    }
+}
+
+/// Lockless hash table, which stores the leaf node count of a position at a given depth
+struct PerftTable {
+    table: Vec<[AtomicU64; 2]>,
+}
+
+impl PerftTable {
+    const MEGABYTE: usize = 1024 * 1024;
+
+    // The depth is stored in the most significant bits of the node count
+    const DEPTH_SHIFT: u64 = 56;
+
+    fn new(mb: usize) -> Self {
+        let size = mb * Self::MEGABYTE / std::mem::size_of::<[AtomicU64; 2]>();
+
+        Self {
+            table: (0..size)
+                .map(|_| [AtomicU64::new(0), AtomicU64::new(0)])
+                .collect(),
+        }
+    }
+
+    fn probe(&self, zobrist: Key, depth: u16) -> Option<u64> {
+        let [key, data] = &self.table[self.index(zobrist)];
+        let (key, data) = (key.load(Ordering::Relaxed), data.load(Ordering::Relaxed));
+
+        // A torn write results in a mismatching key
+        if key ^ data != zobrist || data >> Self::DEPTH_SHIFT != u64::from(depth) {
+            return None;
+        }
+
+        Some(data & ((1 << Self::DEPTH_SHIFT) - 1))
+    }
+
+    fn insert(&self, zobrist: Key, depth: u16, nodes: u64) {
+        debug_assert!(nodes < 1 << Self::DEPTH_SHIFT);
+
+        let [key, data] = &self.table[self.index(zobrist)];
+        let value = nodes | u64::from(depth) << Self::DEPTH_SHIFT;
+
+        key.store(zobrist ^ value, Ordering::Relaxed);
+        data.store(value, Ordering::Relaxed);
+    }
+
+    fn index(&self, zobrist: Key) -> usize {
+        (zobrist % self.table.len() as u64) as usize
+    }
+}
+
+/// Count all leaf nodes at `depth`, where the root moves are split across `threads`
+pub fn perft(pos: &Position, depth: u16, threads: usize) -> Divide {
     if depth == 0 {
-        return 1;
+        return Divide {
+            moves: Vec::new(),
+            nodes: 1,
+        };
     }
 
     let mut moves = MoveList::new();
-    let mut nodes = 0;
+    pos.generate_legal::<All>(&mut moves);
 
-    pos.generate::<All>(&mut moves);
+    let moves: Vec<Move> = moves.iter().collect();
 
-    for mov in moves.iter() {
-        if !pos.legal(mov) {
-            continue;
-        }
+    let table = PerftTable::new(HASH_SIZE);
+    let results = Mutex::new(vec![0; moves.len()]);
+    let next = AtomicUsize::new(0);
 
-        pos.make_move(mov);
-        let child_nodes = perft::<false>(pos, depth - 1);
-        pos.unmake_move(mov);
+    thread::scope(|s| {
+        for _ in 0..threads.max(1) {
+            s.spawn(|| {
+                let mut pos = pos.clone();
+
+                // Each thread takes the next root move, until all are counted
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+
+                    let Some(&mov) = moves.get(index) else {
+                        break;
+                    };
 
-        nodes += child_nodes;
+                    pos.make_move(mov);
+                    let nodes = count(&mut pos, depth - 1, &table);
+                    pos.unmake_move(mov);
 
-        if ROOT {
-            println!("{}: {}", mov, child_nodes);
+                    results.lock().unwrap()[index] = nodes;
+                }
+            });
         }
+    });
+
+    let moves: Vec<(Move, u64)> = moves
+        .into_iter()
+        .zip(results.into_inner().unwrap())
+        .collect();
+    let nodes = moves.iter().map(|(_, nodes)| nodes).sum();
+
+    Divide { moves, nodes }
+}
+
+fn count(pos: &mut Position, depth: u16, table: &PerftTable) -> u64 {
+    if depth == 0 {
+        return 1;
     }
 
-    if ROOT {
-        println!("\nNodes searched: {}", nodes);
+    // We can count the legal moves without making them
+    if depth == 1 {
+        return pos.legal_move_count() as u64;
     }
 
+    let zobrist = pos.zobrist();
+
+    if let Some(nodes) = table.probe(zobrist, depth) {
+        return nodes;
+    }
+
+    let mut moves = MoveList::new();
+    pos.generate_legal::<All>(&mut moves);
+
+    let mut nodes = 0;
+
+    for mov in moves.iter() {
+        pos.make_move(mov);
+        nodes += count(pos, depth - 1, table);
+        pos.unmake_move(mov);
+    }
+
+    table.insert(zobrist, depth, nodes);
+
     nodes
 }
 
 #[cfg(test)]
 mod tests {
+    use std::thread;
+
     use crate::{FEN, chess::Position, util::perft};
 
-    const EXPECTED: [(usize, u16); 6] = [
+    const EXPECTED: [(u64, u16); 6] = [
         (119060324, 6),
         (193690690, 5),
         (178633661, 7),
@@ -48,9 +176,11 @@ mod tests {
 
     #[test]
     fn movegen() {
+        let threads = thread::available_parallelism().map_or(1, usize::from);
+
         for (fen, (nodes, depth)) in FEN[..6].iter().zip(EXPECTED) {
-            let mut pos = Position::from_fen(fen).unwrap();
-            assert_eq!(nodes, perft::<false>(&mut pos, depth));
+            let pos = Position::from_fen(fen).unwrap();
+            assert_eq!(nodes, perft(&pos, depth, threads).nodes);
         }
     }
 }
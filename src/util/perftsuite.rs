@@ -0,0 +1,296 @@
+use std::{fmt::Display, fs, thread};
+
+use types::{Move, MoveFlag, PieceType, Square};
+
+use crate::{
+    chess::{All, MoveList, Position},
+    error::{Error, UciError},
+    util::perft,
+};
+
+/// Expected leaf node counts of a single position by depth
+pub struct Entry {
+    pub fen: String,
+    pub depths: Vec<(u16, u64)>,
+}
+
+/// The first position, whose leaf node count is wrong
+pub struct Mismatch {
+    pub fen: String,
+    pub line: Vec<Move>,
+    pub depth: u16,
+    pub expected: u64,
+    pub found: u64,
+    // Moves which are only legal according to the reference, or to us
+    pub missing: Vec<Move>,
+    pub illegal: Vec<Move>,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Fen: {}", self.fen)?;
+
+        write!(f, "Line:")?;
+        for mov in self.line.iter() {
+            write!(f, " {}", mov)?;
+        }
+
+        write!(
+            f,
+            "\nDepth {}: expected {}, but found {}",
+            self.depth, self.expected, self.found
+        )?;
+
+        for (name, moves) in [("Missing", &self.missing), ("Illegal", &self.illegal)] {
+            if moves.is_empty() {
+                continue;
+            }
+
+            write!(f, "\n{}:", name)?;
+            for mov in moves.iter() {
+                write!(f, " {}", mov)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn perftsuite(args: Vec<&str>) -> Result<(), Error> {
+    let Some(path) = args.get(1) else {
+        return Err(UciError::syntax("<file.epd>", "nothing").into());
+    };
+
+    let epd = match fs::read_to_string(path) {
+        Ok(epd) => epd,
+        Err(err) => return Err(UciError::Io(path.to_string(), err).into()),
+    };
+
+    let entries = parse(&epd)?;
+    let threads = thread::available_parallelism().map_or(1, usize::from);
+
+    let mut failed = 0;
+
+    for (i, entry) in entries.iter().enumerate() {
+        println!("Position: {}/{} ({})", i + 1, entries.len(), entry.fen);
+
+        if let Err(mismatch) = verify(entry, threads)? {
+            println!("{}", mismatch);
+            failed += 1;
+        }
+    }
+
+    println!("\nPassed: {}/{}", entries.len() - failed, entries.len());
+
+    Ok(())
+}
+
+/// Parse EPD lines like `<fen> ;D1 20 ;D2 400`, where the move counters of the FEN are optional
+pub fn parse(epd: &str) -> Result<Vec<Entry>, Error> {
+    let mut entries = Vec::new();
+
+    for line in epd.lines().filter(|line| !line.trim().is_empty()) {
+        let mut fields = line.split(';');
+
+        let mut fen = fields.next().unwrap().trim().to_string();
+        if fen.split_ascii_whitespace().count() == 4 {
+            fen.push_str(" 0 1");
+        }
+
+        let mut depths = Vec::new();
+
+        for field in fields {
+            let (depth, nodes) = match field.split_ascii_whitespace().collect::<Vec<_>>()[..] {
+                [depth, nodes] => (depth, nodes),
+                _ => return Err(UciError::syntax("D<depth> <nodes>", field).into()),
+            };
+
+            let integer = |value: &str| UciError::syntax("integer", value);
+
+            let depth = depth
+                .strip_prefix('D')
+                .ok_or_else(|| UciError::syntax("D<depth>", depth))?;
+
+            depths.push((
+                depth.parse().map_err(|_| integer(depth))?,
+                nodes.parse().map_err(|_| integer(nodes))?,
+            ));
+        }
+
+        entries.push(Entry { fen, depths });
+    }
+
+    Ok(entries)
+}
+
+/// Check every depth of `entry`, and bisect the first wrong one
+pub fn verify(entry: &Entry, threads: usize) -> Result<Result<(), Mismatch>, Error> {
+    let mut pos = Position::from_fen(&entry.fen)?;
+
+    for &(depth, expected) in entry.depths.iter() {
+        let found = perft(&pos, depth, threads).nodes;
+
+        if found == expected {
+            continue;
+        }
+
+        // If the reference agrees with us, the move generation itself is wrong,
+        // so we can only report the root
+        let mismatch = bisect(&mut pos, depth, threads, &mut Vec::new()).unwrap_or(Mismatch {
+            fen: entry.fen.clone(),
+            line: Vec::new(),
+            depth,
+            expected,
+            found,
+            missing: Vec::new(),
+            illegal: Vec::new(),
+        });
+
+        return Ok(Err(mismatch));
+    }
+
+    Ok(Ok(()))
+}
+
+/// Descend into the first subtree, where we disagree with the reference count
+fn bisect(
+    pos: &mut Position,
+    depth: u16,
+    threads: usize,
+    line: &mut Vec<Move>,
+) -> Option<Mismatch> {
+    let divide = perft(pos, depth, threads);
+    let reference = reference(pos, depth);
+
+    let found: Vec<Move> = divide.moves.iter().map(|&(mov, _)| mov).collect();
+    let expected: Vec<Move> = reference.iter().map(|&(mov, _)| mov).collect();
+
+    let contains =
+        |moves: &[Move], mov: Move| moves.iter().any(|other| other.inner() == mov.inner());
+
+    let missing: Vec<Move> = expected
+        .iter()
+        .copied()
+        .filter(|&mov| !contains(&found, mov))
+        .collect();
+    let illegal: Vec<Move> = found
+        .iter()
+        .copied()
+        .filter(|&mov| !contains(&expected, mov))
+        .collect();
+
+    if !missing.is_empty() || !illegal.is_empty() {
+        return Some(Mismatch {
+            fen: pos.fen().unwrap(),
+            line: line.clone(),
+            depth,
+            expected: reference.iter().map(|(_, nodes)| nodes).sum(),
+            found: divide.nodes,
+            missing,
+            illegal,
+        });
+    }
+
+    for ((mov, nodes), (_, expected)) in divide.moves.into_iter().zip(reference) {
+        if nodes == expected {
+            continue;
+        }
+
+        line.push(mov);
+        pos.make_move(mov);
+
+        let mismatch = bisect(pos, depth - 1, threads, line);
+
+        pos.unmake_move(mov);
+        line.pop();
+
+        return mismatch;
+    }
+
+    None
+}
+
+/// Leaf node counts of each root move without hashing, bulk counting or pinned pieces,
+/// which only shares the pseudo-legal move generation with [`perft`]
+fn reference(pos: &mut Position, depth: u16) -> Vec<(Move, u64)> {
+    let mut moves = MoveList::new();
+    pos.generate::<All>(&mut moves);
+
+    let mut divide = Vec::new();
+
+    for mov in moves.iter() {
+        if !legal(pos, mov) {
+            continue;
+        }
+
+        pos.make_move(mov);
+
+        let nodes = match depth {
+            1 => 1,
+            _ => reference(pos, depth - 1)
+                .iter()
+                .map(|(_, nodes)| nodes)
+                .sum(),
+        };
+
+        pos.unmake_move(mov);
+
+        divide.push((mov, nodes));
+    }
+
+    divide
+}
+
+/// Our king must not be attacked after the move, or on its path while castling
+fn legal(pos: &Position, mov: Move) -> bool {
+    let color = pos.stm();
+    let layout = pos.layout();
+
+    let (start, target) = (mov.start(), mov.target());
+
+    if mov.flag() == MoveFlag::KING_CASTLE || mov.flag() == MoveFlag::QUEEN_CASTLE {
+        let path = Square::new((start as u8 + target as u8) / 2).unwrap();
+
+        return [start, path, target]
+            .iter()
+            .all(|&sq| layout.attackers(sq, color, layout.all()).is_empty());
+    }
+
+    // We can't make an illegal move, so we remove the moved and captured pieces by hand
+    let captured = match mov.flag() {
+        MoveFlag::EN_PASSANT => target.set().rotate([56, 8][color]),
+        _ => target.set(),
+    };
+    let occ = ((layout.all() - start.set()) | target.set()) - (captured - target.set());
+
+    let king = match layout.unchecked_at(start) {
+        PieceType::King => target,
+        _ => layout.king(color),
+    };
+
+    (layout.attackers(king, color, occ) - captured).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, thread};
+
+    use crate::util::perftsuite::{parse, verify};
+
+    #[test]
+    fn perftsuite() {
+        let epd = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/res/perftsuite.epd"));
+        let threads = thread::available_parallelism().map_or(1, usize::from);
+
+        for entry in parse(&epd.unwrap()).unwrap() {
+            assert!(verify(&entry, threads).unwrap().is_ok(), "{}", entry.fen);
+        }
+
+        // A wrong count in the EPD is reported at the root
+        let entry = &parse("4k3/8/8/8/8/8/8/4K2R w K - ;D1 15 ;D2 67").unwrap()[0];
+        let mismatch = verify(entry, threads).unwrap().unwrap_err();
+
+        assert_eq!((mismatch.depth, mismatch.found), (2, 66));
+        assert!(mismatch.line.is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::atomic::AtomicBool;
+
+use mort::{
+    All, Collector, MoveList, Position, START_POS, SearchEvent, SearchLimit, TranspositionTable,
+    go, perft,
+};
+
+#[test]
+fn movegen() {
+    let pos = Position::from_fen(START_POS).unwrap();
+
+    let mut moves = MoveList::default();
+    pos.generate_legal::<All>(&mut moves);
+
+    assert_eq!(moves.len(), 20);
+    assert_eq!(perft(&pos, 4, 1).nodes, 197_281);
+
+    assert!(Position::from_fen("8/8/8/8/8/8/8/8 w - - 0 1").is_err());
+}
+
+#[test]
+fn search() {
+    let pos = Position::from_fen(START_POS).unwrap();
+
+    let mut tt = TranspositionTable::default();
+    tt.resize(1);
+
+    let mut limits = SearchLimit::MAX;
+    limits.depth = 3;
+
+    let collector = Collector::default();
+    let (_, mov) = go(&pos, &limits, &tt, &AtomicBool::new(false), &collector);
+
+    let mut moves = MoveList::default();
+    pos.generate_legal::<All>(&mut moves);
+
+    let mov = mov.unwrap();
+    assert!(moves.iter().any(|other| other.inner() == mov.inner()));
+
+    // The observer received the result of the search as well
+    assert!(matches!(
+        collector.events().last(),
+        Some(SearchEvent::BestMove(Some(best))) if best.inner() == mov.inner()
+    ));
+}
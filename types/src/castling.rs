@@ -84,6 +84,11 @@ impl Castling {
         self.set(Self::QUEEN_MASK[color]);
     }
 
+    /// Swaps the castling rights of both [`Color`].
+    pub fn flip(&self) -> Self {
+        Self(((self.0 & 0b0101) << 1) | ((self.0 & 0b1010) >> 1))
+    }
+
     /// Returns `true` if kingside castling is pseudo-legal for the given [`Color`].
     pub fn pseudo_kingside(&self, color: Color, occ: SquareSet) -> bool {
         const OCC: [SquareSet; 2] = [SquareSet(0b0110_0000), SquareSet(0b0110_0000 << 56)];
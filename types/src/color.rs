@@ -4,7 +4,7 @@ use crate::TypeParseError;
 
 /// A side to move.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Color {
     White,
     Black,
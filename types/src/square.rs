@@ -8,7 +8,7 @@ use crate::{File, Rank, SquareSet};
 /// A square on a chessboard.
 #[rustfmt::skip]
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Square {
     A1, B1, C1, D1, E1, F1, G1, H1,
     A2, B2, C2, D2, E2, F2, G2, H2,
@@ -79,6 +79,19 @@ impl Square {
     pub const fn flip(self) -> Self {
         Self::new(self as u8 ^ 0b0111_000).unwrap()
     }
+
+    /// Mirror the [`File`] of given [`Square`].
+    pub const fn mirror(self) -> Self {
+        Self::new(self as u8 ^ 0b0000_111).unwrap()
+    }
+
+    /// Get the number of king moves between two [`Square`].
+    pub const fn distance(self, other: Self) -> u8 {
+        let file = (self.file() as u8).abs_diff(other.file() as u8);
+        let rank = (self.rank() as u8).abs_diff(other.rank() as u8);
+
+        if file > rank { file } else { rank }
+    }
 }
 
 impl<T> Index<Square> for [T; 64] {
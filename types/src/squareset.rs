@@ -61,6 +61,11 @@ impl SquareSet {
         self.0.trailing_zeros() as u8
     }
 
+    /// Get the index of the most significant set bit as a [`u8`].
+    pub fn index_msb(self) -> u8 {
+        63 - self.0.leading_zeros() as u8
+    }
+
     /// Get this [`SquareSet`] with its least significant set bit removed.
     pub fn reset_lsb(self) -> Self {
         self & self.wrapping_sub(Self(1))